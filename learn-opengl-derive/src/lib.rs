@@ -0,0 +1,151 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Fields, LitStr};
+
+/// Derives `learn_opengl::mesh::Vertex` for a `#[repr(C)]` struct with named fields.
+///
+/// Every field becomes an attribute, at locations counting up from zero in field order. Field
+/// types must implement `learn_opengl::mesh::AttributeFormat`, and the struct can't have
+/// padding. Fields take two options:
+///
+/// - `#[vertex(name = "aPos")]` names the shader input, the field name is used otherwise
+/// - `#[vertex(normalized)]` maps integers to 0 to 1, or -1 to 1 when signed
+#[proc_macro_derive(Vertex, attributes(vertex))]
+pub fn derive_vertex(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+struct FieldOptions {
+    name: Option<String>,
+    normalized: bool,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "Vertex can't be derived for generic structs",
+        ));
+    }
+
+    if !is_repr_c(input)? {
+        return Err(syn::Error::new(
+            ident.span(),
+            "Vertex structs need #[repr(C)] so their field offsets are stable",
+        ));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "Vertex can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                ident.span(),
+                "Vertex can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut attributes = Vec::new();
+    let mut types = Vec::new();
+    for (location, field) in fields.iter().enumerate() {
+        let field_ident = field.ident.as_ref().expect("Named fields have names");
+        let ty = &field.ty;
+        let options = field_options(field)?;
+
+        let location = location as u32;
+        let name = options.name.unwrap_or_else(|| field_ident.to_string());
+        let normalized = options.normalized;
+
+        attributes.push(quote! {
+            ::learn_opengl::mesh::VertexAttribute {
+                name: ::std::string::String::from(#name),
+                location: #location,
+                components: <#ty as ::learn_opengl::mesh::AttributeFormat>::COMPONENTS,
+                kind: <#ty as ::learn_opengl::mesh::AttributeFormat>::KIND,
+                normalized: #normalized,
+                offset: ::core::mem::offset_of!(#ident, #field_ident),
+            }
+        });
+        types.push(ty);
+    }
+
+    Ok(quote! {
+        // Safety: repr(C) was checked above and the assertion below rules out padding
+        unsafe impl ::learn_opengl::mesh::Vertex for #ident {
+            fn layout() -> ::learn_opengl::mesh::VertexLayout {
+                ::learn_opengl::mesh::VertexLayout::from_attributes(
+                    ::std::vec![#(#attributes),*],
+                    ::core::mem::size_of::<#ident>(),
+                )
+            }
+        }
+
+        const _: () = ::core::assert!(
+            ::core::mem::size_of::<#ident>() == 0 #(+ ::core::mem::size_of::<#types>())*,
+            "Vertex structs can't have padding, reorder or pad the fields explicitly",
+        );
+    })
+}
+
+fn is_repr_c(input: &DeriveInput) -> syn::Result<bool> {
+    let mut repr_c = false;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("C") {
+                repr_c = true;
+            } else if meta.input.peek(syn::token::Paren) {
+                // align(N) and packed(N) carry a value that isn't needed here
+                let _value;
+                syn::parenthesized!(_value in meta.input);
+            }
+            Ok(())
+        })?;
+    }
+    Ok(repr_c)
+}
+
+fn field_options(field: &syn::Field) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions {
+        name: None,
+        normalized: false,
+    };
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("vertex"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                options.name = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else if meta.path.is_ident("normalized") {
+                options.normalized = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `name = \"...\"` or `normalized`"))
+            }
+        })?;
+    }
+
+    Ok(options)
+}
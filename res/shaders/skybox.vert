@@ -0,0 +1,15 @@
+#version 330 core
+layout (location = 0) in vec3 aPos;
+
+uniform mat4 view;
+uniform mat4 projection;
+
+out vec3 TexCoords;
+
+void main()
+{
+	TexCoords = aPos;
+	vec4 pos = projection * view * vec4(aPos, 1.0);
+	// Depth of 1.0 so the skybox sits behind everything
+	gl_Position = pos.xyww;
+}
\ No newline at end of file
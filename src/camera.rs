@@ -29,6 +29,12 @@ impl Camera {
         glam::Mat4::look_at_rh(self.position, self.position + self.front, self.up)
     }
 
+    /// The view matrix without its translation, for geometry that should stay centered on the
+    /// camera such as a skybox.
+    pub fn get_rotation_viewmatrix(&self) -> glam::Mat4 {
+        glam::Mat4::from_mat3(glam::Mat3::from_mat4(self.get_viewmatrix()))
+    }
+
     pub fn move_position(&mut self, direction: Movement, delta_time: f32) {
         let velocity = delta_time * self.movement_speed;
         match direction {
@@ -0,0 +1,318 @@
+use std::{
+    ffi::{c_void, CString},
+    num::NonZeroU32,
+    os::raw::{c_char, c_float, c_int, c_uint},
+};
+
+type ShaderBinaryFn = unsafe extern "system" fn(
+    count: c_int,
+    shaders: *const c_uint,
+    binary_format: c_uint,
+    binary: *const c_void,
+    length: c_int,
+);
+
+type SpecializeShaderFn = unsafe extern "system" fn(
+    shader: c_uint,
+    entry_point: *const c_char,
+    num_specialization_constants: c_uint,
+    constant_index: *const c_uint,
+    constant_value: *const c_uint,
+);
+
+type ProgramParameteriFn = unsafe extern "system" fn(program: c_uint, name: c_uint, value: c_int);
+type GenProgramPipelinesFn = unsafe extern "system" fn(count: c_int, pipelines: *mut c_uint);
+type DeleteProgramPipelinesFn = unsafe extern "system" fn(count: c_int, pipelines: *const c_uint);
+type UseProgramStagesFn =
+    unsafe extern "system" fn(pipeline: c_uint, stages: c_uint, program: c_uint);
+type BindProgramPipelineFn = unsafe extern "system" fn(pipeline: c_uint);
+type ValidateProgramPipelineFn = unsafe extern "system" fn(pipeline: c_uint);
+type GetProgramPipelineivFn =
+    unsafe extern "system" fn(pipeline: c_uint, name: c_uint, params: *mut c_int);
+type GetProgramPipelineInfoLogFn = unsafe extern "system" fn(
+    pipeline: c_uint,
+    buffer_size: c_int,
+    length: *mut c_int,
+    info_log: *mut c_char,
+);
+type ProgramUniform1iFn = unsafe extern "system" fn(program: c_uint, location: c_int, v0: c_int);
+type ProgramUniform1fFn = unsafe extern "system" fn(program: c_uint, location: c_int, v0: c_float);
+type ProgramUniform3fvFn = unsafe extern "system" fn(
+    program: c_uint,
+    location: c_int,
+    count: c_int,
+    value: *const c_float,
+);
+type ProgramUniformMatrix4fvFn = unsafe extern "system" fn(
+    program: c_uint,
+    location: c_int,
+    count: c_int,
+    transpose: u8,
+    value: *const c_float,
+);
+
+/// `GL_ARB_separate_shader_objects` (core in 4.1), only loaded when every entry point is present.
+struct PipelineFns {
+    program_parameteri: ProgramParameteriFn,
+    gen_program_pipelines: GenProgramPipelinesFn,
+    delete_program_pipelines: DeleteProgramPipelinesFn,
+    use_program_stages: UseProgramStagesFn,
+    bind_program_pipeline: BindProgramPipelineFn,
+    validate_program_pipeline: ValidateProgramPipelineFn,
+    get_program_pipeline_iv: GetProgramPipelineivFn,
+    get_program_pipeline_info_log: GetProgramPipelineInfoLogFn,
+    program_uniform_1_i32: ProgramUniform1iFn,
+    program_uniform_1_f32: ProgramUniform1fFn,
+    program_uniform_3_f32_slice: ProgramUniform3fvFn,
+    program_uniform_matrix_4_f32_slice: ProgramUniformMatrix4fvFn,
+}
+
+/// Entry points that glow does not expose, loaded with the same loader as the `glow::Context`.
+pub struct Extensions {
+    shader_binary: Option<ShaderBinaryFn>,
+    specialize_shader: Option<SpecializeShaderFn>,
+    pipelines: Option<PipelineFns>,
+}
+
+impl Extensions {
+    /// # Safety
+    ///
+    /// The loader must return valid function pointers (or null) for the current context,
+    /// the same requirement as `glow::Context::from_loader_function`.
+    pub unsafe fn from_loader_function<F>(mut loader_function: F) -> Self
+    where
+        F: FnMut(&str) -> *const c_void,
+    {
+        let mut load = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| loader_function(name))
+                .find(|ptr| !ptr.is_null())
+        };
+
+        macro_rules! load {
+            ($fn_type:ty, $($name:literal),+) => {
+                load(&[$($name),+]).map(|ptr| std::mem::transmute::<*const c_void, $fn_type>(ptr))
+            };
+        }
+
+        let shader_binary = load!(ShaderBinaryFn, "glShaderBinary");
+        let specialize_shader = load!(
+            SpecializeShaderFn,
+            "glSpecializeShader",
+            "glSpecializeShaderARB"
+        );
+
+        let mut load_pipelines = || {
+            Some(PipelineFns {
+                program_parameteri: load!(ProgramParameteriFn, "glProgramParameteri")?,
+                gen_program_pipelines: load!(GenProgramPipelinesFn, "glGenProgramPipelines")?,
+                delete_program_pipelines: load!(
+                    DeleteProgramPipelinesFn,
+                    "glDeleteProgramPipelines"
+                )?,
+                use_program_stages: load!(UseProgramStagesFn, "glUseProgramStages")?,
+                bind_program_pipeline: load!(BindProgramPipelineFn, "glBindProgramPipeline")?,
+                validate_program_pipeline: load!(
+                    ValidateProgramPipelineFn,
+                    "glValidateProgramPipeline"
+                )?,
+                get_program_pipeline_iv: load!(GetProgramPipelineivFn, "glGetProgramPipelineiv")?,
+                get_program_pipeline_info_log: load!(
+                    GetProgramPipelineInfoLogFn,
+                    "glGetProgramPipelineInfoLog"
+                )?,
+                program_uniform_1_i32: load!(ProgramUniform1iFn, "glProgramUniform1i")?,
+                program_uniform_1_f32: load!(ProgramUniform1fFn, "glProgramUniform1f")?,
+                program_uniform_3_f32_slice: load!(ProgramUniform3fvFn, "glProgramUniform3fv")?,
+                program_uniform_matrix_4_f32_slice: load!(
+                    ProgramUniformMatrix4fvFn,
+                    "glProgramUniformMatrix4fv"
+                )?,
+            })
+        };
+        let pipelines = load_pipelines();
+
+        Extensions {
+            shader_binary,
+            specialize_shader,
+            pipelines,
+        }
+    }
+
+    /// Whether `GL_ARB_gl_spirv` (core in 4.6) entry points were found.
+    pub fn supports_spirv(&self) -> bool {
+        self.shader_binary.is_some() && self.specialize_shader.is_some()
+    }
+
+    /// Whether `GL_ARB_separate_shader_objects` (core in 4.1) entry points were found.
+    pub fn supports_program_pipelines(&self) -> bool {
+        self.pipelines.is_some()
+    }
+
+    pub(crate) unsafe fn shader_binary(&self, shader: glow::NativeShader, words: &[u32]) {
+        if let Some(shader_binary) = self.shader_binary {
+            shader_binary(
+                1,
+                &shader.0.get(),
+                glow::SHADER_BINARY_FORMAT_SPIR_V,
+                words.as_ptr() as *const c_void,
+                std::mem::size_of_val(words) as c_int,
+            );
+        }
+    }
+
+    pub(crate) unsafe fn specialize_shader(
+        &self,
+        shader: glow::NativeShader,
+        entry_point: &str,
+        constants: &[(u32, u32)],
+    ) {
+        if let Some(specialize_shader) = self.specialize_shader {
+            // Entry point names come from a validated module, so they hold no interior nul
+            let entry_point = CString::new(entry_point).unwrap_or_default();
+            let (indices, values): (Vec<u32>, Vec<u32>) = constants.iter().copied().unzip();
+
+            specialize_shader(
+                shader.0.get(),
+                entry_point.as_ptr(),
+                constants.len() as c_uint,
+                indices.as_ptr(),
+                values.as_ptr(),
+            );
+        }
+    }
+
+    pub(crate) unsafe fn program_parameter_i32(
+        &self,
+        program: glow::NativeProgram,
+        name: u32,
+        value: i32,
+    ) {
+        if let Some(fns) = &self.pipelines {
+            (fns.program_parameteri)(program.0.get(), name, value);
+        }
+    }
+
+    pub(crate) unsafe fn create_program_pipeline(&self) -> Result<NonZeroU32, String> {
+        let fns = match &self.pipelines {
+            Some(fns) => fns,
+            None => return Err(String::from("Program pipelines are not supported")),
+        };
+
+        let mut pipeline = 0;
+        (fns.gen_program_pipelines)(1, &mut pipeline);
+        NonZeroU32::new(pipeline).ok_or_else(|| String::from("Unable to create program pipeline"))
+    }
+
+    pub(crate) unsafe fn delete_program_pipeline(&self, pipeline: NonZeroU32) {
+        if let Some(fns) = &self.pipelines {
+            (fns.delete_program_pipelines)(1, &pipeline.get());
+        }
+    }
+
+    pub(crate) unsafe fn use_program_stages(
+        &self,
+        pipeline: NonZeroU32,
+        stages: u32,
+        program: Option<glow::NativeProgram>,
+    ) {
+        if let Some(fns) = &self.pipelines {
+            (fns.use_program_stages)(pipeline.get(), stages, program.map_or(0, |p| p.0.get()));
+        }
+    }
+
+    pub(crate) unsafe fn bind_program_pipeline(&self, pipeline: Option<NonZeroU32>) {
+        if let Some(fns) = &self.pipelines {
+            (fns.bind_program_pipeline)(pipeline.map_or(0, |p| p.get()));
+        }
+    }
+
+    /// Runs `glValidateProgramPipeline`, returning the info log on failure.
+    pub(crate) unsafe fn validate_program_pipeline(
+        &self,
+        pipeline: NonZeroU32,
+    ) -> Result<(), String> {
+        let fns = match &self.pipelines {
+            Some(fns) => fns,
+            None => return Err(String::from("Program pipelines are not supported")),
+        };
+
+        (fns.validate_program_pipeline)(pipeline.get());
+
+        let mut status = 0;
+        (fns.get_program_pipeline_iv)(pipeline.get(), glow::VALIDATE_STATUS, &mut status);
+        if status != 0 {
+            return Ok(());
+        }
+
+        let mut length = 0;
+        (fns.get_program_pipeline_iv)(pipeline.get(), glow::INFO_LOG_LENGTH, &mut length);
+        let mut log = vec![0u8; length.max(1) as usize];
+        (fns.get_program_pipeline_info_log)(
+            pipeline.get(),
+            log.len() as c_int,
+            &mut length,
+            log.as_mut_ptr() as *mut c_char,
+        );
+        log.truncate(length.max(0) as usize);
+
+        Err(String::from_utf8_lossy(&log).into_owned())
+    }
+
+    pub(crate) unsafe fn program_uniform_1_i32(
+        &self,
+        program: glow::NativeProgram,
+        location: Option<&glow::NativeUniformLocation>,
+        value: i32,
+    ) {
+        if let (Some(fns), Some(location)) = (&self.pipelines, location) {
+            (fns.program_uniform_1_i32)(program.0.get(), location.0 as c_int, value);
+        }
+    }
+
+    pub(crate) unsafe fn program_uniform_1_f32(
+        &self,
+        program: glow::NativeProgram,
+        location: Option<&glow::NativeUniformLocation>,
+        value: f32,
+    ) {
+        if let (Some(fns), Some(location)) = (&self.pipelines, location) {
+            (fns.program_uniform_1_f32)(program.0.get(), location.0 as c_int, value);
+        }
+    }
+
+    pub(crate) unsafe fn program_uniform_3_f32_slice(
+        &self,
+        program: glow::NativeProgram,
+        location: Option<&glow::NativeUniformLocation>,
+        value: &[f32],
+    ) {
+        if let (Some(fns), Some(location)) = (&self.pipelines, location) {
+            (fns.program_uniform_3_f32_slice)(
+                program.0.get(),
+                location.0 as c_int,
+                value.len() as c_int / 3,
+                value.as_ptr(),
+            );
+        }
+    }
+
+    pub(crate) unsafe fn program_uniform_matrix_4_f32_slice(
+        &self,
+        program: glow::NativeProgram,
+        location: Option<&glow::NativeUniformLocation>,
+        transpose: bool,
+        value: &[f32],
+    ) {
+        if let (Some(fns), Some(location)) = (&self.pipelines, location) {
+            (fns.program_uniform_matrix_4_f32_slice)(
+                program.0.get(),
+                location.0 as c_int,
+                value.len() as c_int / 16,
+                transpose as u8,
+                value.as_ptr(),
+            );
+        }
+    }
+}
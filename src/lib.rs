@@ -1,3 +1,12 @@
+// `#[derive(Vertex)]` names the crate by its absolute path, also from inside it
+extern crate self as learn_opengl;
+
 pub mod camera;
+pub mod extensions;
+pub mod material;
+pub mod mesh;
+pub mod pipeline;
 pub mod shader;
+pub mod skybox;
+pub mod spirv;
 pub mod texture;
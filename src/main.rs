@@ -4,13 +4,34 @@ use glfw::{Action, Context, Key};
 use glow::HasContext;
 use learn_opengl::{
     camera::{Camera, Movement},
-    shader::{self, Shader},
-    texture::Texture,
+    material::Material,
+    mesh::{Mesh, Vertex},
+    shader::Shader,
+    texture::TextureCache,
 };
 
 const WIDTH: u32 = 800;
 const HEIGHT: u32 = 600;
 
+#[derive(Clone, Copy, Vertex)]
+#[repr(C)]
+struct CubeVertex {
+    #[vertex(name = "aPos")]
+    position: [f32; 3],
+    #[vertex(name = "aNormal")]
+    normal: [f32; 3],
+    #[vertex(name = "aTexCoords")]
+    tex_coords: [f32; 2],
+}
+
+const fn vertex(position: [f32; 3], normal: [f32; 3], tex_coords: [f32; 2]) -> CubeVertex {
+    CubeVertex {
+        position,
+        normal,
+        tex_coords,
+    }
+}
+
 fn main() {
     // Initialize GLFW
     let mut glfw = glfw::init(glfw::LOG_ERRORS).unwrap();
@@ -39,39 +60,20 @@ fn main() {
         glow::Context::from_loader_function(|s| glfw.get_proc_address_raw(s) as *const _)
     });
 
-    // Shaders
-    let mut lighting_shader = Shader::from_str(
+    // Materials
+    let mut textures = TextureCache::new(Rc::clone(&gl));
+    let container_material = Material::load(
         Rc::clone(&gl),
-        include_str!("../res/shaders/color.vert"),
-        include_str!("../res/shaders/color.frag"),
+        &mut textures,
+        Path::new("res/materials/container.toml"),
     )
     .unwrap_or_else(|e| {
         eprintln!("Error: {}", e);
         std::process::exit(1);
     });
+    let lighting_shader = Rc::clone(container_material.shader());
 
-    {
-        let diffuse_texture = Texture::new(Rc::clone(&gl), Path::new("res/container2.png"))
-            .unwrap_or_else(|e| {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
-            });
-        lighting_shader
-            .add_texture(diffuse_texture, shader::TextureIndex::Index0)
-            .expect("Texture index should not be occupied");
-
-        let specular_texture =
-            Texture::new(Rc::clone(&gl), Path::new("res/container2_specular.png")).unwrap_or_else(
-                |e| {
-                    eprintln!("Error: {}", e);
-                    std::process::exit(1);
-                },
-            );
-        lighting_shader
-            .add_texture(specular_texture, shader::TextureIndex::Index1)
-            .expect("Texture index should not be occupied");
-    }
-
+    // Shaders
     let light_cube_shader = Shader::from_str(
         Rc::clone(&gl),
         include_str!("../res/shaders/light_cube.vert"),
@@ -82,131 +84,72 @@ fn main() {
         std::process::exit(1);
     });
 
-    let (vbo, cube_vao, light_vao) = unsafe {
+    unsafe {
         // View setup
         gl.viewport(0, 0, WIDTH as i32, HEIGHT as i32);
         gl.enable(glow::DEPTH_TEST);
 
         // Wireframe
         // gl.polygon_mode(glow::FRONT_AND_BACK, glow::LINE);
+    }
 
-        // Triangle vertices
-        #[rustfmt::skip]
-        let vertices: [f32; 288] = [
-            // positions          // normals           // texture coords
-            -0.5, -0.5, -0.5,  0.0,  0.0, -1.0,  0.0, 0.0,
-             0.5, -0.5, -0.5,  0.0,  0.0, -1.0,  1.0, 0.0,
-             0.5,  0.5, -0.5,  0.0,  0.0, -1.0,  1.0, 1.0,
-             0.5,  0.5, -0.5,  0.0,  0.0, -1.0,  1.0, 1.0,
-            -0.5,  0.5, -0.5,  0.0,  0.0, -1.0,  0.0, 1.0,
-            -0.5, -0.5, -0.5,  0.0,  0.0, -1.0,  0.0, 0.0,
-
-            -0.5, -0.5,  0.5,  0.0,  0.0, 1.0,   0.0, 0.0,
-             0.5, -0.5,  0.5,  0.0,  0.0, 1.0,   1.0, 0.0,
-             0.5,  0.5,  0.5,  0.0,  0.0, 1.0,   1.0, 1.0,
-             0.5,  0.5,  0.5,  0.0,  0.0, 1.0,   1.0, 1.0,
-            -0.5,  0.5,  0.5,  0.0,  0.0, 1.0,   0.0, 1.0,
-            -0.5, -0.5,  0.5,  0.0,  0.0, 1.0,   0.0, 0.0,
-
-            -0.5,  0.5,  0.5, -1.0,  0.0,  0.0,  1.0, 0.0,
-            -0.5,  0.5, -0.5, -1.0,  0.0,  0.0,  1.0, 1.0,
-            -0.5, -0.5, -0.5, -1.0,  0.0,  0.0,  0.0, 1.0,
-            -0.5, -0.5, -0.5, -1.0,  0.0,  0.0,  0.0, 1.0,
-            -0.5, -0.5,  0.5, -1.0,  0.0,  0.0,  0.0, 0.0,
-            -0.5,  0.5,  0.5, -1.0,  0.0,  0.0,  1.0, 0.0,
-
-             0.5,  0.5,  0.5,  1.0,  0.0,  0.0,  1.0, 0.0,
-             0.5,  0.5, -0.5,  1.0,  0.0,  0.0,  1.0, 1.0,
-             0.5, -0.5, -0.5,  1.0,  0.0,  0.0,  0.0, 1.0,
-             0.5, -0.5, -0.5,  1.0,  0.0,  0.0,  0.0, 1.0,
-             0.5, -0.5,  0.5,  1.0,  0.0,  0.0,  0.0, 0.0,
-             0.5,  0.5,  0.5,  1.0,  0.0,  0.0,  1.0, 0.0,
-
-            -0.5, -0.5, -0.5,  0.0, -1.0,  0.0,  0.0, 1.0,
-             0.5, -0.5, -0.5,  0.0, -1.0,  0.0,  1.0, 1.0,
-             0.5, -0.5,  0.5,  0.0, -1.0,  0.0,  1.0, 0.0,
-             0.5, -0.5,  0.5,  0.0, -1.0,  0.0,  1.0, 0.0,
-            -0.5, -0.5,  0.5,  0.0, -1.0,  0.0,  0.0, 0.0,
-            -0.5, -0.5, -0.5,  0.0, -1.0,  0.0,  0.0, 1.0,
-
-            -0.5,  0.5, -0.5,  0.0,  1.0,  0.0,  0.0, 1.0,
-             0.5,  0.5, -0.5,  0.0,  1.0,  0.0,  1.0, 1.0,
-             0.5,  0.5,  0.5,  0.0,  1.0,  0.0,  1.0, 0.0,
-             0.5,  0.5,  0.5,  0.0,  1.0,  0.0,  1.0, 0.0,
-            -0.5,  0.5,  0.5,  0.0,  1.0,  0.0,  0.0, 0.0,
-            -0.5,  0.5, -0.5,  0.0,  1.0,  0.0,  0.0, 1.0
-        ];
-
-        // Vertex Array Object
-        let cube_vao = gl
-            .create_vertex_array()
-            .expect("Failed to create vertex array");
-
-        gl.bind_vertex_array(Some(cube_vao));
-
-        // Vertex Buffer Object
-        let vbo = gl.create_buffer().expect("Failed to create vertex buffer");
-        gl.bind_buffer(glow::ARRAY_BUFFER, Some(vbo));
-        gl.buffer_data_u8_slice(
-            glow::ARRAY_BUFFER,
-            vertices.align_to::<u8>().1,
-            glow::STATIC_DRAW,
-        );
-
-        // aPos position attribute
-        gl.vertex_attrib_pointer_f32(
-            0,
-            3,
-            glow::FLOAT,
-            false,
-            std::mem::size_of::<f32>() as i32 * 8,
-            0,
-        );
-        gl.enable_vertex_attrib_array(0);
-
-        // aNormal
-        gl.vertex_attrib_pointer_f32(
-            1,
-            3,
-            glow::FLOAT,
-            false,
-            std::mem::size_of::<f32>() as i32 * 8,
-            std::mem::size_of::<f32>() as i32 * 3,
-        );
-        gl.enable_vertex_attrib_array(1);
-
-        // aNormal
-        gl.vertex_attrib_pointer_f32(
-            2,
-            2,
-            glow::FLOAT,
-            false,
-            std::mem::size_of::<f32>() as i32 * 8,
-            std::mem::size_of::<f32>() as i32 * 6,
-        );
-        gl.enable_vertex_attrib_array(2);
-
-        let light_vao = gl
-            .create_vertex_array()
-            .expect("Failed to create vertex array");
-
-        gl.bind_vertex_array(Some(light_vao));
-
-        gl.bind_buffer(glow::ARRAY_BUFFER, Some(vbo));
-
-        // aPos position attribute
-        gl.vertex_attrib_pointer_f32(
-            0,
-            3,
-            glow::FLOAT,
-            false,
-            std::mem::size_of::<f32>() as i32 * 8,
-            0,
-        );
-        gl.enable_vertex_attrib_array(0);
-
-        (vbo, cube_vao, light_vao)
-    };
+    // Cube vertices
+    #[rustfmt::skip]
+    let vertices = [
+        vertex([-0.5, -0.5, -0.5], [ 0.0,  0.0, -1.0], [ 0.0,  0.0]),
+        vertex([ 0.5, -0.5, -0.5], [ 0.0,  0.0, -1.0], [ 1.0,  0.0]),
+        vertex([ 0.5,  0.5, -0.5], [ 0.0,  0.0, -1.0], [ 1.0,  1.0]),
+        vertex([ 0.5,  0.5, -0.5], [ 0.0,  0.0, -1.0], [ 1.0,  1.0]),
+        vertex([-0.5,  0.5, -0.5], [ 0.0,  0.0, -1.0], [ 0.0,  1.0]),
+        vertex([-0.5, -0.5, -0.5], [ 0.0,  0.0, -1.0], [ 0.0,  0.0]),
+
+        vertex([-0.5, -0.5,  0.5], [ 0.0,  0.0,  1.0], [ 0.0,  0.0]),
+        vertex([ 0.5, -0.5,  0.5], [ 0.0,  0.0,  1.0], [ 1.0,  0.0]),
+        vertex([ 0.5,  0.5,  0.5], [ 0.0,  0.0,  1.0], [ 1.0,  1.0]),
+        vertex([ 0.5,  0.5,  0.5], [ 0.0,  0.0,  1.0], [ 1.0,  1.0]),
+        vertex([-0.5,  0.5,  0.5], [ 0.0,  0.0,  1.0], [ 0.0,  1.0]),
+        vertex([-0.5, -0.5,  0.5], [ 0.0,  0.0,  1.0], [ 0.0,  0.0]),
+
+        vertex([-0.5,  0.5,  0.5], [-1.0,  0.0,  0.0], [ 1.0,  0.0]),
+        vertex([-0.5,  0.5, -0.5], [-1.0,  0.0,  0.0], [ 1.0,  1.0]),
+        vertex([-0.5, -0.5, -0.5], [-1.0,  0.0,  0.0], [ 0.0,  1.0]),
+        vertex([-0.5, -0.5, -0.5], [-1.0,  0.0,  0.0], [ 0.0,  1.0]),
+        vertex([-0.5, -0.5,  0.5], [-1.0,  0.0,  0.0], [ 0.0,  0.0]),
+        vertex([-0.5,  0.5,  0.5], [-1.0,  0.0,  0.0], [ 1.0,  0.0]),
+
+        vertex([ 0.5,  0.5,  0.5], [ 1.0,  0.0,  0.0], [ 1.0,  0.0]),
+        vertex([ 0.5,  0.5, -0.5], [ 1.0,  0.0,  0.0], [ 1.0,  1.0]),
+        vertex([ 0.5, -0.5, -0.5], [ 1.0,  0.0,  0.0], [ 0.0,  1.0]),
+        vertex([ 0.5, -0.5, -0.5], [ 1.0,  0.0,  0.0], [ 0.0,  1.0]),
+        vertex([ 0.5, -0.5,  0.5], [ 1.0,  0.0,  0.0], [ 0.0,  0.0]),
+        vertex([ 0.5,  0.5,  0.5], [ 1.0,  0.0,  0.0], [ 1.0,  0.0]),
+
+        vertex([-0.5, -0.5, -0.5], [ 0.0, -1.0,  0.0], [ 0.0,  1.0]),
+        vertex([ 0.5, -0.5, -0.5], [ 0.0, -1.0,  0.0], [ 1.0,  1.0]),
+        vertex([ 0.5, -0.5,  0.5], [ 0.0, -1.0,  0.0], [ 1.0,  0.0]),
+        vertex([ 0.5, -0.5,  0.5], [ 0.0, -1.0,  0.0], [ 1.0,  0.0]),
+        vertex([-0.5, -0.5,  0.5], [ 0.0, -1.0,  0.0], [ 0.0,  0.0]),
+        vertex([-0.5, -0.5, -0.5], [ 0.0, -1.0,  0.0], [ 0.0,  1.0]),
+
+        vertex([-0.5,  0.5, -0.5], [ 0.0,  1.0,  0.0], [ 0.0,  1.0]),
+        vertex([ 0.5,  0.5, -0.5], [ 0.0,  1.0,  0.0], [ 1.0,  1.0]),
+        vertex([ 0.5,  0.5,  0.5], [ 0.0,  1.0,  0.0], [ 1.0,  0.0]),
+        vertex([ 0.5,  0.5,  0.5], [ 0.0,  1.0,  0.0], [ 1.0,  0.0]),
+        vertex([-0.5,  0.5,  0.5], [ 0.0,  1.0,  0.0], [ 0.0,  0.0]),
+        vertex([-0.5,  0.5, -0.5], [ 0.0,  1.0,  0.0], [ 0.0,  1.0]),
+    ];
+
+    // The light cube shader only reads aPos, so both draw the same mesh
+    for shader in [&*lighting_shader, &light_cube_shader] {
+        CubeVertex::layout().check(shader).unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        });
+    }
+    let cube = Mesh::from_vertices(Rc::clone(&gl), &vertices).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
 
     // Frame Timing
     let mut delta_time;
@@ -246,14 +189,10 @@ fn main() {
             gl.clear_color(0.1, 0.1, 0.1, 1.0);
             gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
 
-            // Shader
-            lighting_shader.bind();
-
-            // Color / lighting
-            lighting_shader.set_int("material.diffuse", 0);
-            lighting_shader.set_int("material.specular", 1);
-            lighting_shader.set_float("material.shininess", 32.0);
+            // Material
+            container_material.apply();
 
+            // Lighting
             lighting_shader.set_vec3("light.position", light_pos);
 
             let light_color = glam::vec3(
@@ -286,7 +225,6 @@ fn main() {
 
             lighting_shader.set_mat4("projection", false, &projection);
             lighting_shader.set_mat4("view", false, &view);
-            gl.bind_vertex_array(Some(cube_vao));
             // World transformations
             for (i, position) in cube_positions.iter().enumerate() {
                 let mut model = glam::Mat4::IDENTITY * glam::Mat4::from_translation(*position);
@@ -295,7 +233,7 @@ fn main() {
                 lighting_shader.set_mat4("model", false, &model);
 
                 // Draw cube model
-                gl.draw_arrays(glow::TRIANGLES, 0, 36);
+                cube.draw();
             }
 
             // Draw light model
@@ -312,8 +250,7 @@ fn main() {
 
             light_cube_shader.set_mat4("model", false, &light_cube_model);
 
-            gl.bind_vertex_array(Some(light_vao));
-            gl.draw_arrays(glow::TRIANGLES, 0, 36);
+            cube.draw();
         }
 
         // Swap buffers and poll
@@ -362,11 +299,4 @@ fn main() {
             }
         }
     }
-
-    // Cleanup
-    unsafe {
-        gl.delete_vertex_array(cube_vao);
-        gl.delete_buffer(vbo);
-        // gl.delete_buffer(ebo);
-    }
 }
@@ -0,0 +1,338 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    shader,
+    shader::{Shader, TextureError},
+    texture,
+    texture::{
+        ColorSpace, Filter, Mipmaps, Texture, TextureCache, TextureMeta, TextureOptions, WrapMode,
+    },
+};
+
+/// A typed uniform value, one variant per `Shader::set_*` method.
+///
+/// In TOML, numbers are floats whether or not they have a fractional part, since `32` for a
+/// `float` uniform is far more common than an `int` uniform. Ints are written as `{ int = 32 }`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(from = "ParameterValue")]
+pub enum Parameter {
+    Bool(bool),
+    Int(i32),
+    Float(f32),
+    Vec3(glam::Vec3),
+    Mat4(glam::Mat4),
+}
+
+/// How a [`Parameter`] is written in a material description.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ParameterValue {
+    Bool(bool),
+    Float(f32),
+    Vec3(glam::Vec3),
+    Mat4(glam::Mat4),
+    Int { int: i32 },
+}
+
+/// A texture of a [`MaterialDescription`], written as a path or as a table with a `path` and the
+/// settings of a [`TextureMeta`] sidecar, apart from `compressed`.
+///
+/// The settings go on top of [`TextureOptions::default`], a sidecar next to the image still
+/// overrides them.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(from = "TextureEntry")]
+pub struct MaterialTexture {
+    pub path: PathBuf,
+    pub settings: TextureMeta,
+}
+
+/// How a [`MaterialTexture`] is written in a material description.
+#[derive(Deserialize)]
+#[serde(untagged, deny_unknown_fields)]
+enum TextureEntry {
+    Path(PathBuf),
+    Table {
+        path: PathBuf,
+        color_space: Option<ColorSpace>,
+        wrap: Option<WrapMode>,
+        filter: Option<Filter>,
+        mipmaps: Option<Mipmaps>,
+        flip_vertically: Option<bool>,
+    },
+}
+
+/// A shader plus the textures and uniform values that make up one surface.
+///
+/// Several materials can share one `Rc<Shader>` and differ only in their data.
+pub struct Material {
+    shader: Rc<Shader>,
+    textures: Vec<TextureSlot>,
+    parameters: Vec<(String, Parameter)>,
+}
+
+/// A texture of a [`Material`] and the unit its sampler uniform was pointed at.
+struct TextureSlot {
+    name: String,
+    unit: u32,
+    texture: Rc<Texture>,
+}
+
+/// On-disk layout of a material, paths are relative to the description file.
+///
+/// ```toml
+/// vertex_shader = "../shaders/color.vert"
+/// fragment_shader = "../shaders/color.frag"
+///
+/// [textures]
+/// "material.diffuse" = { path = "../container2.png", color_space = "srgb" }
+/// "material.specular" = "../container2_specular.png"
+///
+/// [parameters]
+/// "material.shininess" = 32.0
+/// "material.layer" = { int = 2 }
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct MaterialDescription {
+    pub vertex_shader: PathBuf,
+    pub fragment_shader: PathBuf,
+    #[serde(default)]
+    pub textures: BTreeMap<String, MaterialTexture>,
+    #[serde(default)]
+    pub parameters: BTreeMap<String, Parameter>,
+}
+
+#[derive(Error, Debug)]
+pub enum MaterialError {
+    #[error("Failed to open file: {error_message}")]
+    FileOpenFailed {
+        error_message: String,
+        file_path: PathBuf,
+        io_error: io::Error,
+    },
+
+    #[error("Failed to parse material {path:?}: {error}")]
+    ParseFailed {
+        path: PathBuf,
+        error: toml::de::Error,
+    },
+
+    #[error("Failed to create material shader: {error}")]
+    ShaderCreationFailed { error: shader::CreationError },
+
+    #[error("Failed to load material texture: {error}")]
+    TextureCreationFailed { error: texture::CreationError },
+
+    #[error("Failed to bind material texture: {error}")]
+    TextureBindingFailed { error: TextureError },
+}
+
+impl Material {
+    pub fn new(shader: Rc<Shader>) -> Self {
+        Material {
+            shader,
+            textures: Vec::new(),
+            parameters: Vec::new(),
+        }
+    }
+
+    /// Loads a [`MaterialDescription`] from a TOML file, compiling its shader and loading its
+    /// textures through `textures`, so materials using the same files share them.
+    pub fn load(
+        gl: Rc<glow::Context>,
+        textures: &mut TextureCache,
+        path: &Path,
+    ) -> Result<Self, MaterialError> {
+        let source = Self::read_file(path)?;
+        let description: MaterialDescription =
+            toml::from_str(&source).map_err(|error| MaterialError::ParseFailed {
+                path: PathBuf::from(path),
+                error,
+            })?;
+
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+        let shader = Shader::from_str(
+            Rc::clone(&gl),
+            &Self::read_file(&directory.join(&description.vertex_shader))?,
+            &Self::read_file(&directory.join(&description.fragment_shader))?,
+        )
+        .map_err(|error| MaterialError::ShaderCreationFailed { error })?;
+
+        let mut material = Material::new(Rc::new(shader));
+
+        for (name, texture) in &description.textures {
+            let options = texture.settings.apply(&TextureOptions::default());
+            let texture = textures
+                .load(&directory.join(&texture.path), &options)
+                .map_err(|error| MaterialError::TextureCreationFailed { error })?;
+            material
+                .set_texture(name, texture)
+                .map_err(|error| MaterialError::TextureBindingFailed { error })?;
+        }
+
+        for (name, parameter) in description.parameters {
+            material.set_parameter(&name, parameter);
+        }
+
+        Ok(material)
+    }
+
+    pub fn shader(&self) -> &Rc<Shader> {
+        &self.shader
+    }
+
+    /// Sets the texture for the sampler uniform `name`, returning the one it replaces.
+    ///
+    /// A new sampler takes the next unit the shader's own [`Shader::bind_texture`] bindings and
+    /// the other textures of this material leave free, and the uniform is pointed at it right
+    /// away, which makes the shader current. Materials sharing a shader should set the same
+    /// samplers in the same order so they agree on units.
+    pub fn set_texture(
+        &mut self,
+        name: &str,
+        texture: impl Into<Rc<Texture>>,
+    ) -> Result<Option<Rc<Texture>>, TextureError> {
+        let texture = texture.into();
+        if let Some(slot) = self.textures.iter_mut().find(|slot| slot.name == name) {
+            return Ok(Some(std::mem::replace(&mut slot.texture, texture)));
+        }
+
+        let unit = self
+            .shader
+            .free_texture_units()
+            .find(|unit| self.textures.iter().all(|slot| slot.unit != *unit))
+            .ok_or(TextureError::NoFreeTextureUnit {
+                max_units: self.shader.max_texture_units(),
+            })?;
+        self.shader.set_sampler_unit(name, unit);
+
+        self.textures.push(TextureSlot {
+            name: name.to_string(),
+            unit,
+            texture,
+        });
+        Ok(None)
+    }
+
+    pub fn set_parameter(&mut self, name: &str, value: impl Into<Parameter>) {
+        let value = value.into();
+        match self.parameters.iter_mut().find(|(n, _)| n == name) {
+            Some((_, existing)) => *existing = value,
+            None => self.parameters.push((name.to_string(), value)),
+        }
+    }
+
+    pub fn parameter(&self, name: &str) -> Option<Parameter> {
+        self.parameters
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| *value)
+    }
+
+    /// Binds the shader, then every texture and parameter of this material.
+    ///
+    /// Textures go to the units picked in [`Material::set_texture`].
+    pub fn apply(&self) {
+        self.shader.bind();
+
+        for slot in &self.textures {
+            self.shader.bind_to_unit(slot.unit, &slot.texture);
+        }
+
+        for (name, parameter) in &self.parameters {
+            match *parameter {
+                Parameter::Bool(value) => self.shader.set_bool(name, value),
+                Parameter::Int(value) => self.shader.set_int(name, value),
+                Parameter::Float(value) => self.shader.set_float(name, value),
+                Parameter::Vec3(value) => self.shader.set_vec3(name, value),
+                Parameter::Mat4(value) => self.shader.set_mat4(name, false, &value),
+            }
+        }
+    }
+
+    fn read_file(path: &Path) -> Result<String, MaterialError> {
+        std::fs::read_to_string(path).map_err(|err| MaterialError::FileOpenFailed {
+            error_message: err.to_string(),
+            file_path: PathBuf::from(path),
+            io_error: err,
+        })
+    }
+}
+
+impl From<TextureEntry> for MaterialTexture {
+    fn from(entry: TextureEntry) -> Self {
+        match entry {
+            TextureEntry::Path(path) => MaterialTexture {
+                path,
+                settings: TextureMeta::default(),
+            },
+            TextureEntry::Table {
+                path,
+                color_space,
+                wrap,
+                filter,
+                mipmaps,
+                flip_vertically,
+            } => MaterialTexture {
+                path,
+                settings: TextureMeta {
+                    color_space,
+                    wrap,
+                    filter,
+                    mipmaps,
+                    flip_vertically,
+                    compressed: None,
+                },
+            },
+        }
+    }
+}
+
+impl From<ParameterValue> for Parameter {
+    fn from(value: ParameterValue) -> Self {
+        match value {
+            ParameterValue::Bool(value) => Parameter::Bool(value),
+            ParameterValue::Float(value) => Parameter::Float(value),
+            ParameterValue::Vec3(value) => Parameter::Vec3(value),
+            ParameterValue::Mat4(value) => Parameter::Mat4(value),
+            ParameterValue::Int { int } => Parameter::Int(int),
+        }
+    }
+}
+
+impl From<bool> for Parameter {
+    fn from(value: bool) -> Self {
+        Parameter::Bool(value)
+    }
+}
+
+impl From<i32> for Parameter {
+    fn from(value: i32) -> Self {
+        Parameter::Int(value)
+    }
+}
+
+impl From<f32> for Parameter {
+    fn from(value: f32) -> Self {
+        Parameter::Float(value)
+    }
+}
+
+impl From<glam::Vec3> for Parameter {
+    fn from(value: glam::Vec3) -> Self {
+        Parameter::Vec3(value)
+    }
+}
+
+impl From<glam::Mat4> for Parameter {
+    fn from(value: glam::Mat4) -> Self {
+        Parameter::Mat4(value)
+    }
+}
@@ -0,0 +1,436 @@
+use std::rc::Rc;
+
+use glow::HasContext;
+use thiserror::Error;
+
+use crate::shader::Shader;
+
+pub use learn_opengl_derive::Vertex;
+
+/// Vertex data on the GPU: a vertex array object with its vertex buffer and optional index
+/// buffer, drawn as triangles.
+pub struct Mesh {
+    gl: Rc<glow::Context>,
+    vao: glow::NativeVertexArray,
+    vbo: glow::NativeBuffer,
+    ebo: Option<glow::NativeBuffer>,
+    vertex_count: usize,
+    index_count: Option<usize>,
+}
+
+/// How the vertices in a buffer are laid out, one [`VertexAttribute`] per shader input.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VertexLayout {
+    attributes: Vec<VertexAttribute>,
+    stride: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VertexAttribute {
+    /// Name of the shader input, for error messages and checks against a shader
+    pub name: String,
+    pub location: u32,
+    /// One to four
+    pub components: u32,
+    pub kind: AttributeType,
+    /// Whether integers are mapped to 0 to 1, or -1 to 1 when signed, instead of converted as is
+    pub normalized: bool,
+    /// Bytes from the start of the vertex
+    pub offset: usize,
+}
+
+/// Component types of a [`VertexAttribute`], integers reach the shader as floats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeType {
+    Float,
+    Byte,
+    UnsignedByte,
+    Short,
+    UnsignedShort,
+    Int,
+    UnsignedInt,
+}
+
+/// A vertex struct whose memory matches its [`VertexLayout`], usually through
+/// `#[derive(Vertex)]`.
+///
+/// # Safety
+///
+/// The type must be `#[repr(C)]` without padding bytes, and [`Vertex::layout`] must describe
+/// its fields, since [`Mesh::from_vertices`] uploads its memory as is.
+pub unsafe trait Vertex: Copy {
+    fn layout() -> VertexLayout;
+}
+
+/// Field types `#[derive(Vertex)]` accepts, with the attribute they become.
+pub trait AttributeFormat {
+    const COMPONENTS: u32;
+    const KIND: AttributeType;
+}
+
+#[derive(Error, Debug)]
+pub enum CreationError {
+    #[error("Failed to create mesh buffers: {error_message}")]
+    BufferCreationFailed { error_message: String },
+
+    #[error("{len} bytes of vertex data are not a whole number of {stride} byte vertices")]
+    VertexDataSizeMismatch { len: usize, stride: usize },
+
+    #[error("Index {index} is out of range for {vertex_count} vertices")]
+    IndexOutOfRange { index: u32, vertex_count: usize },
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum LayoutError {
+    #[error("Shader input {name:?} at location {location} has no vertex attribute")]
+    MissingAttribute { name: String, location: u32 },
+
+    #[error("Shader input {name:?} reads {expected} components, the layout has {found}")]
+    ComponentMismatch {
+        name: String,
+        expected: u32,
+        found: u32,
+    },
+
+    #[error("Shader input {name:?} is an integer, mesh attributes are always read as floats")]
+    IntegerInput { name: String },
+}
+
+macro_rules! attribute_format {
+    ($($ty:ty => $kind:ident),* $(,)?) => {
+        $(
+            impl AttributeFormat for $ty {
+                const COMPONENTS: u32 = 1;
+                const KIND: AttributeType = AttributeType::$kind;
+            }
+
+            impl<const N: usize> AttributeFormat for [$ty; N] {
+                const COMPONENTS: u32 = {
+                    assert!(N >= 1 && N <= 4, "Vertex attributes have one to four components");
+                    N as u32
+                };
+                const KIND: AttributeType = AttributeType::$kind;
+            }
+        )*
+    };
+}
+
+attribute_format! {
+    f32 => Float,
+    i8 => Byte,
+    u8 => UnsignedByte,
+    i16 => Short,
+    u16 => UnsignedShort,
+    i32 => Int,
+    u32 => UnsignedInt,
+}
+
+impl AttributeFormat for glam::Vec2 {
+    const COMPONENTS: u32 = 2;
+    const KIND: AttributeType = AttributeType::Float;
+}
+
+impl AttributeFormat for glam::Vec3 {
+    const COMPONENTS: u32 = 3;
+    const KIND: AttributeType = AttributeType::Float;
+}
+
+impl AttributeFormat for glam::Vec4 {
+    const COMPONENTS: u32 = 4;
+    const KIND: AttributeType = AttributeType::Float;
+}
+
+impl AttributeType {
+    pub fn size(self) -> usize {
+        match self {
+            AttributeType::Byte | AttributeType::UnsignedByte => 1,
+            AttributeType::Short | AttributeType::UnsignedShort => 2,
+            AttributeType::Float | AttributeType::Int | AttributeType::UnsignedInt => 4,
+        }
+    }
+
+    pub fn gl_enum(self) -> u32 {
+        match self {
+            AttributeType::Float => glow::FLOAT,
+            AttributeType::Byte => glow::BYTE,
+            AttributeType::UnsignedByte => glow::UNSIGNED_BYTE,
+            AttributeType::Short => glow::SHORT,
+            AttributeType::UnsignedShort => glow::UNSIGNED_SHORT,
+            AttributeType::Int => glow::INT,
+            AttributeType::UnsignedInt => glow::UNSIGNED_INT,
+        }
+    }
+}
+
+impl VertexLayout {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends an attribute right after the previous one, at the next location.
+    ///
+    /// Panics unless `components` is between one and four.
+    pub fn attribute(
+        mut self,
+        name: &str,
+        components: u32,
+        kind: AttributeType,
+        normalized: bool,
+    ) -> Self {
+        assert!(
+            (1..=4).contains(&components),
+            "Vertex attributes have one to four components, {name:?} has {components}"
+        );
+
+        self.attributes.push(VertexAttribute {
+            name: name.to_string(),
+            location: self.attributes.len() as u32,
+            components,
+            kind,
+            normalized,
+            offset: self.stride,
+        });
+        self.stride += components as usize * kind.size();
+        self
+    }
+
+    /// A layout with explicit offsets and stride, as `#[derive(Vertex)]` builds them.
+    pub fn from_attributes(attributes: Vec<VertexAttribute>, stride: usize) -> Self {
+        VertexLayout { attributes, stride }
+    }
+
+    pub fn attributes(&self) -> &[VertexAttribute] {
+        &self.attributes
+    }
+
+    /// Bytes from one vertex to the next.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Checks that every input `shader` reads has a float attribute at its location with as
+    /// many components. Attributes the shader doesn't read are fine.
+    pub fn check(&self, shader: &Shader) -> Result<(), LayoutError> {
+        for input in shader.active_attributes() {
+            let Some(attribute) = self
+                .attributes
+                .iter()
+                .find(|attribute| attribute.location == input.location)
+            else {
+                return Err(LayoutError::MissingAttribute {
+                    name: input.name,
+                    location: input.location,
+                });
+            };
+
+            let expected = match input.gl_type {
+                glow::FLOAT => 1,
+                glow::FLOAT_VEC2 => 2,
+                glow::FLOAT_VEC3 => 3,
+                glow::FLOAT_VEC4 => 4,
+                glow::INT
+                | glow::INT_VEC2
+                | glow::INT_VEC3
+                | glow::INT_VEC4
+                | glow::UNSIGNED_INT
+                | glow::UNSIGNED_INT_VEC2
+                | glow::UNSIGNED_INT_VEC3
+                | glow::UNSIGNED_INT_VEC4 => {
+                    return Err(LayoutError::IntegerInput { name: input.name })
+                }
+                // Matrices and doubles span several locations or need other entry points
+                _ => continue,
+            };
+
+            if attribute.components != expected {
+                return Err(LayoutError::ComponentMismatch {
+                    name: input.name,
+                    expected,
+                    found: attribute.components,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Points every attribute at the buffer bound to `ARRAY_BUFFER` and enables it.
+    unsafe fn apply(&self, gl: &glow::Context) {
+        for attribute in &self.attributes {
+            gl.vertex_attrib_pointer_f32(
+                attribute.location,
+                attribute.components as i32,
+                attribute.kind.gl_enum(),
+                attribute.normalized,
+                self.stride as i32,
+                attribute.offset as i32,
+            );
+            gl.enable_vertex_attrib_array(attribute.location);
+        }
+    }
+}
+
+impl Mesh {
+    /// Uploads vertices laid out as `layout` describes, drawn in order.
+    pub fn new(
+        gl: Rc<glow::Context>,
+        layout: &VertexLayout,
+        vertex_data: &[u8],
+    ) -> Result<Self, CreationError> {
+        Self::create(gl, layout, vertex_data, None)
+    }
+
+    /// Uploads vertices laid out as `layout` describes, drawn in the order of `indices`.
+    pub fn with_indices(
+        gl: Rc<glow::Context>,
+        layout: &VertexLayout,
+        vertex_data: &[u8],
+        indices: &[u32],
+    ) -> Result<Self, CreationError> {
+        Self::create(gl, layout, vertex_data, Some(indices))
+    }
+
+    /// Uploads typed vertices, drawn in order.
+    pub fn from_vertices<V: Vertex>(
+        gl: Rc<glow::Context>,
+        vertices: &[V],
+    ) -> Result<Self, CreationError> {
+        Self::new(gl, &V::layout(), vertex_bytes(vertices))
+    }
+
+    /// Uploads typed vertices, drawn in the order of `indices`.
+    pub fn from_vertices_with_indices<V: Vertex>(
+        gl: Rc<glow::Context>,
+        vertices: &[V],
+        indices: &[u32],
+    ) -> Result<Self, CreationError> {
+        Self::with_indices(gl, &V::layout(), vertex_bytes(vertices), indices)
+    }
+
+    fn create(
+        gl: Rc<glow::Context>,
+        layout: &VertexLayout,
+        vertex_data: &[u8],
+        indices: Option<&[u32]>,
+    ) -> Result<Self, CreationError> {
+        let vertex_count = match vertex_data.len().checked_div(layout.stride()) {
+            Some(count) if count * layout.stride() == vertex_data.len() => count,
+            _ => {
+                return Err(CreationError::VertexDataSizeMismatch {
+                    len: vertex_data.len(),
+                    stride: layout.stride(),
+                })
+            }
+        };
+
+        if let Some(&index) = indices
+            .into_iter()
+            .flatten()
+            .find(|&&index| index as usize >= vertex_count)
+        {
+            return Err(CreationError::IndexOutOfRange {
+                index,
+                vertex_count,
+            });
+        }
+
+        unsafe {
+            let vao = gl
+                .create_vertex_array()
+                .map_err(|error_message| CreationError::BufferCreationFailed { error_message })?;
+
+            let vbo = match gl.create_buffer() {
+                Ok(vbo) => vbo,
+                Err(error_message) => {
+                    gl.delete_vertex_array(vao);
+                    return Err(CreationError::BufferCreationFailed { error_message });
+                }
+            };
+
+            let ebo = match indices.map(|_| gl.create_buffer()).transpose() {
+                Ok(ebo) => ebo,
+                Err(error_message) => {
+                    gl.delete_buffer(vbo);
+                    gl.delete_vertex_array(vao);
+                    return Err(CreationError::BufferCreationFailed { error_message });
+                }
+            };
+
+            gl.bind_vertex_array(Some(vao));
+
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(vbo));
+            gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, vertex_data, glow::STATIC_DRAW);
+            layout.apply(&gl);
+
+            // The element buffer binding is part of the VAO, so it stays bound
+            if let (Some(ebo), Some(indices)) = (ebo, indices) {
+                gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(ebo));
+                gl.buffer_data_u8_slice(
+                    glow::ELEMENT_ARRAY_BUFFER,
+                    indices.align_to::<u8>().1,
+                    glow::STATIC_DRAW,
+                );
+            }
+
+            gl.bind_vertex_array(None);
+            gl.bind_buffer(glow::ARRAY_BUFFER, None);
+
+            Ok(Mesh {
+                gl,
+                vao,
+                vbo,
+                ebo,
+                vertex_count,
+                index_count: indices.map(|indices| indices.len()),
+            })
+        }
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.vertex_count
+    }
+
+    /// Number of indices, `None` for meshes drawn in vertex order.
+    pub fn index_count(&self) -> Option<usize> {
+        self.index_count
+    }
+
+    /// Draws the mesh as triangles with whatever shader is bound.
+    pub fn draw(&self) {
+        unsafe {
+            self.gl.bind_vertex_array(Some(self.vao));
+            match self.index_count {
+                Some(count) => {
+                    self.gl
+                        .draw_elements(glow::TRIANGLES, count as i32, glow::UNSIGNED_INT, 0)
+                }
+                None => self
+                    .gl
+                    .draw_arrays(glow::TRIANGLES, 0, self.vertex_count as i32),
+            }
+            self.gl.bind_vertex_array(None);
+        }
+    }
+}
+
+fn vertex_bytes<V: Vertex>(vertices: &[V]) -> &[u8] {
+    // Safety: Vertex types are repr(C) without padding, so every byte is initialized
+    unsafe {
+        std::slice::from_raw_parts(
+            vertices.as_ptr() as *const u8,
+            std::mem::size_of_val(vertices),
+        )
+    }
+}
+
+impl Drop for Mesh {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.delete_vertex_array(self.vao);
+            self.gl.delete_buffer(self.vbo);
+            if let Some(ebo) = self.ebo {
+                self.gl.delete_buffer(ebo);
+            }
+        }
+    }
+}
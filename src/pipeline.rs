@@ -0,0 +1,515 @@
+use std::{num::NonZeroU32, rc::Rc};
+
+use glow::HasContext;
+use thiserror::Error;
+
+use crate::{
+    extensions::Extensions,
+    shader::{CreationError, Shader, ShaderType},
+};
+
+/// A single-stage program linked with `GL_PROGRAM_SEPARABLE`, to be combined in a [`ProgramPipeline`].
+pub struct SeparableProgram {
+    gl: Rc<glow::Context>,
+    extensions: Rc<Extensions>,
+    program: glow::NativeProgram,
+    stage: ShaderType,
+    inputs: Vec<InterfaceVariable>,
+    outputs: Vec<InterfaceVariable>,
+}
+
+/// Program pipeline object that mixes stages from separately linked programs.
+pub struct ProgramPipeline {
+    gl: Rc<glow::Context>,
+    extensions: Rc<Extensions>,
+    pipeline: NonZeroU32,
+    vertex: Option<Rc<SeparableProgram>>,
+    fragment: Option<Rc<SeparableProgram>>,
+}
+
+/// A global `in` or `out` declaration of a stage, interface blocks are matched by block name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfaceVariable {
+    pub name: String,
+    pub ty: String,
+    pub location: Option<u32>,
+}
+
+#[derive(Error, Debug)]
+pub enum PipelineError {
+    #[error("Program pipelines are not supported by this context")]
+    PipelinesUnsupported,
+
+    #[error("Failed to create program pipeline: {error_message}")]
+    PipelineCreationFailed { error_message: String },
+
+    #[error("Fragment input {name:?} has no matching vertex output")]
+    MissingOutput { name: String },
+
+    #[error("Interface mismatch for {name:?}: only one stage declares a location, vertex {output_location:?}, fragment {input_location:?}")]
+    LocationMismatch {
+        name: String,
+        output_location: Option<u32>,
+        input_location: Option<u32>,
+    },
+
+    #[error("Interface mismatch for {name:?}: vertex outputs {output_type}, fragment expects {input_type}")]
+    InterfaceMismatch {
+        name: String,
+        output_type: String,
+        input_type: String,
+    },
+
+    #[error("Program pipeline validation failed: {error_message}")]
+    ValidationFailed { error_message: String },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    In,
+    Out,
+}
+
+impl InterfaceVariable {
+    /// Global `in` declarations of GLSL `source`, ignoring function bodies and built-ins.
+    pub fn inputs(source: &str) -> Vec<Self> {
+        parse_interface(source, Direction::In)
+    }
+
+    /// Global `out` declarations of GLSL `source`, ignoring function bodies and built-ins.
+    pub fn outputs(source: &str) -> Vec<Self> {
+        parse_interface(source, Direction::Out)
+    }
+}
+
+impl SeparableProgram {
+    pub fn from_str(
+        gl: Rc<glow::Context>,
+        extensions: Rc<Extensions>,
+        stage: ShaderType,
+        source: &str,
+    ) -> Result<Self, CreationError> {
+        // Without pipelines the program would quietly link as an ordinary one
+        if !extensions.supports_program_pipelines() {
+            return Err(CreationError::SeparableProgramsUnsupported);
+        }
+
+        let shader = Shader::compile_shader(Rc::clone(&gl), stage, source)?;
+
+        unsafe {
+            let program = match gl.create_program() {
+                Ok(p) => p,
+                Err(err) => {
+                    gl.delete_shader(shader);
+                    return Err(CreationError::ProgramCreationFailed { error_message: err });
+                }
+            };
+
+            extensions.program_parameter_i32(program, glow::PROGRAM_SEPARABLE, glow::TRUE as i32);
+
+            gl.attach_shader(program, shader);
+            gl.link_program(program);
+            gl.detach_shader(program, shader);
+            gl.delete_shader(shader);
+
+            if !gl.get_program_link_status(program) {
+                let error_message = gl.get_program_info_log(program);
+                gl.delete_program(program);
+                return Err(CreationError::ProgramCompilationFailed { error_message });
+            }
+
+            Ok(SeparableProgram {
+                gl,
+                extensions,
+                program,
+                stage,
+                inputs: InterfaceVariable::inputs(source),
+                outputs: InterfaceVariable::outputs(source),
+            })
+        }
+    }
+
+    pub fn stage(&self) -> ShaderType {
+        self.stage
+    }
+
+    pub fn inputs(&self) -> &[InterfaceVariable] {
+        &self.inputs
+    }
+
+    pub fn outputs(&self) -> &[InterfaceVariable] {
+        &self.outputs
+    }
+
+    pub fn set_bool(&self, name: &str, value: bool) {
+        self.set_int(name, value as i32)
+    }
+
+    pub fn set_int(&self, name: &str, value: i32) {
+        unsafe {
+            self.extensions.program_uniform_1_i32(
+                self.program,
+                self.gl.get_uniform_location(self.program, name).as_ref(),
+                value,
+            )
+        }
+    }
+
+    pub fn set_float(&self, name: &str, value: f32) {
+        unsafe {
+            self.extensions.program_uniform_1_f32(
+                self.program,
+                self.gl.get_uniform_location(self.program, name).as_ref(),
+                value,
+            )
+        }
+    }
+
+    pub fn set_vec3(&self, name: &str, value: glam::Vec3) {
+        unsafe {
+            self.extensions.program_uniform_3_f32_slice(
+                self.program,
+                self.gl.get_uniform_location(self.program, name).as_ref(),
+                &value.to_array(),
+            )
+        }
+    }
+
+    pub fn set_mat4(&self, name: &str, transpose: bool, value: &glam::Mat4) {
+        unsafe {
+            self.extensions.program_uniform_matrix_4_f32_slice(
+                self.program,
+                self.gl.get_uniform_location(self.program, name).as_ref(),
+                transpose,
+                &value.to_cols_array(),
+            )
+        }
+    }
+}
+
+impl Drop for SeparableProgram {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.delete_program(self.program);
+        }
+    }
+}
+
+impl ProgramPipeline {
+    pub fn new(gl: Rc<glow::Context>, extensions: Rc<Extensions>) -> Result<Self, PipelineError> {
+        if !extensions.supports_program_pipelines() {
+            return Err(PipelineError::PipelinesUnsupported);
+        }
+
+        let pipeline = unsafe { extensions.create_program_pipeline() }
+            .map_err(|error_message| PipelineError::PipelineCreationFailed { error_message })?;
+
+        Ok(ProgramPipeline {
+            gl,
+            extensions,
+            pipeline,
+            vertex: None,
+            fragment: None,
+        })
+    }
+
+    /// Installs `program` for its stage, returning the program it replaces.
+    ///
+    /// The interface between the vertex outputs and fragment inputs is checked before anything changes.
+    pub fn use_stage(
+        &mut self,
+        program: Rc<SeparableProgram>,
+    ) -> Result<Option<Rc<SeparableProgram>>, PipelineError> {
+        match program.stage {
+            ShaderType::VertexShader => {
+                if let Some(fragment) = &self.fragment {
+                    check_interface(&program.outputs, &fragment.inputs)?;
+                }
+            }
+            ShaderType::FragmentShader => {
+                if let Some(vertex) = &self.vertex {
+                    check_interface(&vertex.outputs, &program.inputs)?;
+                }
+            }
+        }
+
+        unsafe {
+            self.extensions.use_program_stages(
+                self.pipeline,
+                stage_bit(program.stage),
+                Some(program.program),
+            );
+        }
+
+        let slot = match program.stage {
+            ShaderType::VertexShader => &mut self.vertex,
+            ShaderType::FragmentShader => &mut self.fragment,
+        };
+        Ok(slot.replace(program))
+    }
+
+    pub fn stage(&self, stage: ShaderType) -> Option<&Rc<SeparableProgram>> {
+        match stage {
+            ShaderType::VertexShader => self.vertex.as_ref(),
+            ShaderType::FragmentShader => self.fragment.as_ref(),
+        }
+    }
+
+    /// Asks the driver whether the pipeline can execute with the current state.
+    pub fn validate(&self) -> Result<(), PipelineError> {
+        unsafe { self.extensions.validate_program_pipeline(self.pipeline) }
+            .map_err(|error_message| PipelineError::ValidationFailed { error_message })
+    }
+
+    /// Binds the pipeline, a program made current with `use_program` would take precedence so it is cleared.
+    pub fn bind(&self) {
+        unsafe {
+            self.gl.use_program(None);
+            self.extensions.bind_program_pipeline(Some(self.pipeline));
+        }
+    }
+}
+
+impl Drop for ProgramPipeline {
+    fn drop(&mut self) {
+        unsafe {
+            self.extensions.delete_program_pipeline(self.pipeline);
+        }
+    }
+}
+
+fn stage_bit(stage: ShaderType) -> u32 {
+    match stage {
+        ShaderType::VertexShader => glow::VERTEX_SHADER_BIT,
+        ShaderType::FragmentShader => glow::FRAGMENT_SHADER_BIT,
+    }
+}
+
+/// Checks that every fragment input has a vertex output of the same type.
+///
+/// Variables are matched by location when both declare one and by name when neither does. GL
+/// doesn't match a variable with a location to one without, so that is a mismatch.
+pub fn check_interface(
+    outputs: &[InterfaceVariable],
+    inputs: &[InterfaceVariable],
+) -> Result<(), PipelineError> {
+    for input in inputs {
+        let output = match input.location {
+            Some(location) => outputs
+                .iter()
+                .find(|output| output.location == Some(location))
+                .or_else(|| {
+                    outputs
+                        .iter()
+                        .find(|output| output.location.is_none() && output.name == input.name)
+                }),
+            None => outputs.iter().find(|output| output.name == input.name),
+        };
+
+        let output = match output {
+            Some(output) => output,
+            None => {
+                return Err(PipelineError::MissingOutput {
+                    name: input.name.clone(),
+                })
+            }
+        };
+
+        if output.location.is_some() != input.location.is_some() {
+            return Err(PipelineError::LocationMismatch {
+                name: input.name.clone(),
+                output_location: output.location,
+                input_location: input.location,
+            });
+        }
+
+        if output.ty != input.ty {
+            return Err(PipelineError::InterfaceMismatch {
+                name: input.name.clone(),
+                output_type: output.ty.clone(),
+                input_type: input.ty.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Collects global `in`/`out` declarations from GLSL source, ignoring function bodies and built-ins.
+fn parse_interface(source: &str, direction: Direction) -> Vec<InterfaceVariable> {
+    let mut variables = Vec::new();
+
+    for statement in global_statements(&strip_comments(source)) {
+        let (location, rest) = take_layout(&statement);
+
+        // Interface blocks: `out VS_OUT { ... } vs_out`
+        let (head, is_block) = match rest.find('{') {
+            Some(brace) => (&rest[..brace], true),
+            None => (rest.as_str(), false),
+        };
+
+        let tokens: Vec<&str> = head
+            .split_whitespace()
+            .filter(|token| !is_qualifier(token))
+            .collect();
+
+        let declared = match tokens.first() {
+            Some(&"in") => Direction::In,
+            Some(&"out") => Direction::Out,
+            _ => continue,
+        };
+        if declared != direction {
+            continue;
+        }
+
+        if is_block {
+            if let Some(block_name) = tokens.get(1) {
+                variables.push(InterfaceVariable {
+                    name: block_name.to_string(),
+                    ty: String::from("block"),
+                    location,
+                });
+            }
+            continue;
+        }
+
+        // `out vec3 a, b[2]` declares several variables of one type
+        let declaration = tokens[1..].join(" ");
+        let (ty, names) = match declaration.split_once(char::is_whitespace) {
+            Some(split) => split,
+            None => continue,
+        };
+
+        for name in names.split(',') {
+            let name: String = name.chars().filter(|c| !c.is_whitespace()).collect();
+            let (name, array) = match name.find('[') {
+                Some(bracket) => (name[..bracket].to_string(), name[bracket..].to_string()),
+                None => (name, String::new()),
+            };
+
+            if name.is_empty() || name.starts_with("gl_") {
+                continue;
+            }
+
+            variables.push(InterfaceVariable {
+                name,
+                ty: format!("{}{}", ty.replace(char::is_whitespace, ""), array),
+                location,
+            });
+        }
+    }
+
+    variables
+}
+
+fn is_qualifier(token: &str) -> bool {
+    matches!(
+        token,
+        "flat"
+            | "smooth"
+            | "noperspective"
+            | "centroid"
+            | "sample"
+            | "invariant"
+            | "precise"
+            | "highp"
+            | "mediump"
+            | "lowp"
+    )
+}
+
+fn strip_comments(source: &str) -> String {
+    let mut stripped = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        stripped.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                stripped.push(' ');
+            }
+            _ => stripped.push(c),
+        }
+    }
+
+    // Preprocessor directives such as `#version` are not declarations
+    stripped
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Splits source into `;` terminated statements at global scope, dropping function definitions.
+fn global_statements(source: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+
+    for c in source.chars() {
+        match c {
+            '{' => {
+                depth += 1;
+                current.push(c);
+            }
+            '}' => {
+                depth = depth.saturating_sub(1);
+                current.push(c);
+
+                // A function body closes without a `;`, the parentheses of a block's layout
+                // qualifier don't make it a function
+                let head = current.split('{').next().unwrap_or("");
+                if depth == 0 && take_layout(head).1.contains('(') {
+                    current.clear();
+                }
+            }
+            ';' if depth == 0 => {
+                statements.push(current.trim().to_string());
+                current.clear();
+            }
+            _ => current.push(c),
+        }
+    }
+
+    statements
+}
+
+/// Removes a leading `layout(...)` qualifier, returning its `location` if it had one.
+fn take_layout(statement: &str) -> (Option<u32>, String) {
+    let start = match statement.find("layout") {
+        Some(start) => start,
+        None => return (None, statement.to_string()),
+    };
+
+    let (open, close) = match (statement[start..].find('('), statement[start..].find(')')) {
+        (Some(open), Some(close)) if open < close => (start + open, start + close),
+        _ => return (None, statement.to_string()),
+    };
+
+    let location = statement[open + 1..close].split(',').find_map(|qualifier| {
+        let (key, value) = qualifier.split_once('=')?;
+        if key.trim() == "location" {
+            value.trim().parse().ok()
+        } else {
+            None
+        }
+    });
+
+    let rest = format!("{} {}", &statement[..start], &statement[close + 1..]);
+    (location, rest)
+}
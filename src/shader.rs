@@ -4,38 +4,39 @@ use glow::HasContext;
 
 use thiserror::Error;
 
-use crate::texture::Texture;
+use crate::{
+    extensions::Extensions,
+    spirv::{Specialization, SpirvError, SpirvModule},
+    texture::Texture,
+};
 
 pub struct Shader {
     gl: Rc<glow::Context>,
     program: glow::NativeProgram,
-    textures: [Option<Texture>; 16],
+    textures: Vec<TextureBinding>,
+    max_texture_units: u32,
+}
+
+struct TextureBinding {
+    name: String,
+    unit: u32,
+    texture: Rc<Texture>,
 }
 
 #[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ShaderType {
     VertexShader = glow::VERTEX_SHADER,
     FragmentShader = glow::FRAGMENT_SHADER,
 }
 
-#[derive(Debug, Clone, Copy)]
-pub enum TextureIndex {
-    Index0 = 0,
-    Index1,
-    Index2,
-    Index3,
-    Index4,
-    Index5,
-    Index6,
-    Index7,
-    Index8,
-    Index9,
-    Index10,
-    Index11,
-    Index12,
-    Index13,
-    Index14,
-    Index15,
+/// A vertex shader input the linker kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActiveAttribute {
+    pub name: String,
+    pub location: u32,
+    /// GL type such as `FLOAT_VEC3`
+    pub gl_type: u32,
 }
 
 #[derive(Error, Debug)]
@@ -51,12 +52,24 @@ pub enum CreationError {
 
     #[error("Failed to compile program: {error_message}")]
     ProgramCompilationFailed { error_message: String },
+
+    #[error("SPIR-V shaders are not supported by this context")]
+    SpirvUnsupported,
+
+    #[error("Separable programs are not supported by this context")]
+    SeparableProgramsUnsupported,
+
+    #[error("Invalid SPIR-V module: {error}")]
+    InvalidSpirv { error: SpirvError },
 }
 
 #[derive(Error, Debug)]
 pub enum TextureError {
-    #[error("Texture index already in use")]
-    TextureIndexTaken { index: TextureIndex },
+    #[error("No active sampler uniform named {name:?}")]
+    UnknownSampler { name: String },
+
+    #[error("All {max_units} texture units are in use")]
+    NoFreeTextureUnit { max_units: u32 },
 }
 
 impl Shader {
@@ -71,8 +84,85 @@ impl Shader {
 
         // Fragment Shader
         let compiled_fragment_shader =
-            Self::compile_shader(Rc::clone(&gl), ShaderType::FragmentShader, fragment_shader)?;
+            match Self::compile_shader(Rc::clone(&gl), ShaderType::FragmentShader, fragment_shader)
+            {
+                Ok(shader) => shader,
+                Err(err) => {
+                    unsafe { gl.delete_shader(compiled_vertex_shader) };
+                    return Err(err);
+                }
+            };
 
+        Self::link(gl, compiled_vertex_shader, compiled_fragment_shader)
+    }
+
+    /// Creates a program from SPIR-V modules, specializing both stages at their `main` entry point.
+    pub fn from_spirv(
+        gl: Rc<glow::Context>,
+        extensions: &Extensions,
+        vertex_shader: &SpirvModule,
+        fragment_shader: &SpirvModule,
+    ) -> Result<Self, CreationError> {
+        Self::from_spirv_specialized(
+            gl,
+            extensions,
+            vertex_shader,
+            &Specialization::default(),
+            fragment_shader,
+            &Specialization::default(),
+        )
+    }
+
+    pub fn from_spirv_specialized(
+        gl: Rc<glow::Context>,
+        extensions: &Extensions,
+        vertex_shader: &SpirvModule,
+        vertex_specialization: &Specialization,
+        fragment_shader: &SpirvModule,
+        fragment_specialization: &Specialization,
+    ) -> Result<Self, CreationError> {
+        if !extensions.supports_spirv() {
+            return Err(CreationError::SpirvUnsupported);
+        }
+
+        // Catch bad modules and constants here, drivers tend to report them poorly if at all
+        vertex_shader
+            .validate(ShaderType::VertexShader, vertex_specialization)
+            .map_err(|error| CreationError::InvalidSpirv { error })?;
+        fragment_shader
+            .validate(ShaderType::FragmentShader, fragment_specialization)
+            .map_err(|error| CreationError::InvalidSpirv { error })?;
+
+        let compiled_vertex_shader = Self::specialize_shader(
+            Rc::clone(&gl),
+            extensions,
+            ShaderType::VertexShader,
+            vertex_shader,
+            vertex_specialization,
+        )?;
+
+        let compiled_fragment_shader = match Self::specialize_shader(
+            Rc::clone(&gl),
+            extensions,
+            ShaderType::FragmentShader,
+            fragment_shader,
+            fragment_specialization,
+        ) {
+            Ok(shader) => shader,
+            Err(err) => {
+                unsafe { gl.delete_shader(compiled_vertex_shader) };
+                return Err(err);
+            }
+        };
+
+        Self::link(gl, compiled_vertex_shader, compiled_fragment_shader)
+    }
+
+    fn link(
+        gl: Rc<glow::Context>,
+        compiled_vertex_shader: glow::NativeShader,
+        compiled_fragment_shader: glow::NativeShader,
+    ) -> Result<Self, CreationError> {
         unsafe {
             let cleanup = || {
                 // Delete shaders
@@ -93,10 +183,10 @@ impl Shader {
             gl.link_program(program);
 
             if !gl.get_program_link_status(program) {
+                let error_message = gl.get_program_info_log(program);
                 cleanup();
-                return Err(CreationError::ProgramCompilationFailed {
-                    error_message: gl.get_program_info_log(program),
-                });
+                gl.delete_program(program);
+                return Err(CreationError::ProgramCompilationFailed { error_message });
             }
 
             // Unlink shaders then delete
@@ -105,27 +195,110 @@ impl Shader {
 
             cleanup();
 
+            let max_texture_units = gl.get_parameter_i32(glow::MAX_TEXTURE_IMAGE_UNITS) as u32;
+
             Ok(Shader {
                 gl,
                 program,
-                textures: Default::default(),
+                textures: Vec::new(),
+                max_texture_units,
             })
         }
     }
 
-    pub fn add_texture(
+    /// Vertex inputs the program reads, built-ins such as `gl_VertexID` left out.
+    pub fn active_attributes(&self) -> Vec<ActiveAttribute> {
+        unsafe {
+            (0..self.gl.get_active_attributes(self.program))
+                .filter_map(|index| {
+                    let attribute = self.gl.get_active_attribute(self.program, index)?;
+                    let location = self.gl.get_attrib_location(self.program, &attribute.name)?;
+                    Some(ActiveAttribute {
+                        name: attribute.name,
+                        location,
+                        gl_type: attribute.atype,
+                    })
+                })
+                .collect()
+        }
+    }
+
+    /// Binds `texture` to the sampler uniform `name`, assigning it the lowest free texture unit.
+    ///
+    /// The sampler uniform is only set when a new unit is assigned, which leaves this program in use.
+    /// Binding to a name that already has a texture replaces it and returns the old one.
+    ///
+    /// Pass an `Rc<Texture>` to share one GPU texture between several shaders, it is deleted once
+    /// the last handle is dropped.
+    pub fn bind_texture(
         &mut self,
-        texture: Texture,
-        index: TextureIndex,
-    ) -> Result<(), TextureError> {
-        if self.textures[index as usize].is_some() {
-            return Err(TextureError::TextureIndexTaken { index });
+        name: &str,
+        texture: impl Into<Rc<Texture>>,
+    ) -> Result<Option<Rc<Texture>>, TextureError> {
+        let texture = texture.into();
+        if let Some(binding) = self.textures.iter_mut().find(|b| b.name == name) {
+            return Ok(Some(std::mem::replace(&mut binding.texture, texture)));
         }
-        self.textures[index as usize] = Some(texture);
-        Ok(())
+
+        unsafe {
+            let location = match self.gl.get_uniform_location(self.program, name) {
+                Some(location) => location,
+                None => {
+                    return Err(TextureError::UnknownSampler {
+                        name: name.to_string(),
+                    })
+                }
+            };
+
+            let max_units = self.max_texture_units;
+            let unit =
+                match (0..max_units).find(|unit| self.textures.iter().all(|b| b.unit != *unit)) {
+                    Some(unit) => unit,
+                    None => return Err(TextureError::NoFreeTextureUnit { max_units }),
+                };
+
+            self.gl.use_program(Some(self.program));
+            self.gl.uniform_1_i32(Some(&location), unit as i32);
+
+            self.textures.push(TextureBinding {
+                name: name.to_string(),
+                unit,
+                texture,
+            });
+        }
+
+        Ok(None)
+    }
+
+    /// Removes the texture bound to `name`, freeing its texture unit.
+    pub fn remove_texture(&mut self, name: &str) -> Option<Rc<Texture>> {
+        let index = self.textures.iter().position(|b| b.name == name)?;
+        Some(self.textures.remove(index).texture)
     }
 
-    fn compile_shader(
+    /// `MAX_TEXTURE_IMAGE_UNITS` of the context, queried once when the program was linked.
+    pub fn max_texture_units(&self) -> u32 {
+        self.max_texture_units
+    }
+
+    /// Texture units not claimed by [`Shader::bind_texture`].
+    pub(crate) fn free_texture_units(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.max_texture_units).filter(|unit| self.textures.iter().all(|b| b.unit != *unit))
+    }
+
+    /// Binds `texture` to `unit`.
+    pub(crate) fn bind_to_unit(&self, unit: u32, texture: &Texture) {
+        unsafe { self.gl.active_texture(glow::TEXTURE0 + unit) }
+        texture.bind();
+    }
+
+    /// Points the sampler uniform `name` at `unit`, making this program the current one.
+    pub(crate) fn set_sampler_unit(&self, name: &str, unit: u32) {
+        unsafe { self.gl.use_program(Some(self.program)) }
+        self.set_int(name, unit as i32);
+    }
+
+    pub(crate) fn compile_shader(
         gl: Rc<glow::Context>,
         shader_type: ShaderType,
         source: &str,
@@ -140,9 +313,40 @@ impl Shader {
             gl.compile_shader(shader);
 
             if !gl.get_shader_compile_status(shader) {
-                Err(CreationError::ShaderCompilationFailed {
-                    error_message: gl.get_shader_info_log(shader),
-                })
+                let error_message = gl.get_shader_info_log(shader);
+                gl.delete_shader(shader);
+                Err(CreationError::ShaderCompilationFailed { error_message })
+            } else {
+                Ok(shader)
+            }
+        }
+    }
+
+    fn specialize_shader(
+        gl: Rc<glow::Context>,
+        extensions: &Extensions,
+        shader_type: ShaderType,
+        module: &SpirvModule,
+        specialization: &Specialization,
+    ) -> Result<glow::NativeShader, CreationError> {
+        unsafe {
+            let shader = match gl.create_shader(shader_type as u32) {
+                Ok(shader) => shader,
+                Err(err) => return Err(CreationError::ShaderCreationFailed { error_message: err }),
+            };
+
+            extensions.shader_binary(shader, module.words());
+            extensions.specialize_shader(
+                shader,
+                specialization.entry_point(),
+                specialization.constants(),
+            );
+
+            // Specialization reports through the regular compile status
+            if !gl.get_shader_compile_status(shader) {
+                let error_message = gl.get_shader_info_log(shader);
+                gl.delete_shader(shader);
+                Err(CreationError::ShaderCompilationFailed { error_message })
             } else {
                 Ok(shader)
             }
@@ -150,31 +354,11 @@ impl Shader {
     }
 
     pub fn bind(&self) {
-        let texture_id = [
-            glow::TEXTURE0,
-            glow::TEXTURE1,
-            glow::TEXTURE2,
-            glow::TEXTURE3,
-            glow::TEXTURE4,
-            glow::TEXTURE5,
-            glow::TEXTURE6,
-            glow::TEXTURE7,
-            glow::TEXTURE8,
-            glow::TEXTURE9,
-            glow::TEXTURE10,
-            glow::TEXTURE11,
-            glow::TEXTURE12,
-            glow::TEXTURE13,
-            glow::TEXTURE14,
-            glow::TEXTURE15,
-        ];
         unsafe {
             self.gl.use_program(Some(self.program));
-            for (index, texture) in self.textures.iter().enumerate() {
-                if let Some(texture) = texture {
-                    self.gl.active_texture(texture_id[index]);
-                    texture.bind();
-                }
+            for binding in &self.textures {
+                self.gl.active_texture(glow::TEXTURE0 + binding.unit);
+                binding.texture.bind();
             }
         }
     }
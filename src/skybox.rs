@@ -0,0 +1,127 @@
+use std::rc::Rc;
+
+use glow::HasContext;
+use thiserror::Error;
+
+use crate::{
+    camera::Camera,
+    mesh,
+    mesh::{Mesh, Vertex},
+    shader,
+    shader::Shader,
+    texture::Cubemap,
+};
+
+/// Draws a cubemap around the camera, behind everything already in the depth buffer.
+pub struct Skybox {
+    gl: Rc<glow::Context>,
+    shader: Shader,
+    mesh: Mesh,
+}
+
+#[derive(Clone, Copy, Vertex)]
+#[repr(C)]
+struct SkyboxVertex {
+    #[vertex(name = "aPos")]
+    position: [f32; 3],
+}
+
+#[derive(Error, Debug)]
+pub enum CreationError {
+    #[error("Failed to create skybox shader: {error}")]
+    ShaderCreationFailed { error: shader::CreationError },
+
+    #[error("Skybox vertices don't match its shader: {error}")]
+    LayoutMismatch { error: mesh::LayoutError },
+
+    #[error("Failed to create skybox mesh: {error}")]
+    MeshCreationFailed { error: mesh::CreationError },
+}
+
+impl Skybox {
+    pub fn new(gl: Rc<glow::Context>) -> Result<Self, CreationError> {
+        let shader = Shader::from_str(
+            Rc::clone(&gl),
+            include_str!("../res/shaders/skybox.vert"),
+            include_str!("../res/shaders/skybox.frag"),
+        )
+        .map_err(|error| CreationError::ShaderCreationFailed { error })?;
+
+        #[rustfmt::skip]
+        let positions: [[f32; 3]; 36] = [
+            [-1.0,  1.0, -1.0],
+            [-1.0, -1.0, -1.0],
+            [ 1.0, -1.0, -1.0],
+            [ 1.0, -1.0, -1.0],
+            [ 1.0,  1.0, -1.0],
+            [-1.0,  1.0, -1.0],
+
+            [-1.0, -1.0,  1.0],
+            [-1.0, -1.0, -1.0],
+            [-1.0,  1.0, -1.0],
+            [-1.0,  1.0, -1.0],
+            [-1.0,  1.0,  1.0],
+            [-1.0, -1.0,  1.0],
+
+            [ 1.0, -1.0, -1.0],
+            [ 1.0, -1.0,  1.0],
+            [ 1.0,  1.0,  1.0],
+            [ 1.0,  1.0,  1.0],
+            [ 1.0,  1.0, -1.0],
+            [ 1.0, -1.0, -1.0],
+
+            [-1.0, -1.0,  1.0],
+            [-1.0,  1.0,  1.0],
+            [ 1.0,  1.0,  1.0],
+            [ 1.0,  1.0,  1.0],
+            [ 1.0, -1.0,  1.0],
+            [-1.0, -1.0,  1.0],
+
+            [-1.0,  1.0, -1.0],
+            [ 1.0,  1.0, -1.0],
+            [ 1.0,  1.0,  1.0],
+            [ 1.0,  1.0,  1.0],
+            [-1.0,  1.0,  1.0],
+            [-1.0,  1.0, -1.0],
+
+            [-1.0, -1.0, -1.0],
+            [-1.0, -1.0,  1.0],
+            [ 1.0, -1.0, -1.0],
+            [ 1.0, -1.0, -1.0],
+            [-1.0, -1.0,  1.0],
+            [ 1.0, -1.0,  1.0],
+        ];
+
+        let vertices = positions.map(|position| SkyboxVertex { position });
+
+        SkyboxVertex::layout()
+            .check(&shader)
+            .map_err(|error| CreationError::LayoutMismatch { error })?;
+        let mesh = Mesh::from_vertices(Rc::clone(&gl), &vertices)
+            .map_err(|error| CreationError::MeshCreationFailed { error })?;
+
+        Ok(Skybox { gl, shader, mesh })
+    }
+
+    /// Draws `cubemap` using the camera's rotation only, so the sky never gets closer.
+    ///
+    /// Draw it after opaque geometry, it passes the depth test only where nothing was drawn.
+    pub fn draw(&self, cubemap: &Cubemap, camera: &Camera, projection: &glam::Mat4) {
+        unsafe {
+            self.gl.depth_func(glow::LEQUAL);
+
+            self.shader.bind();
+            self.shader
+                .set_mat4("view", false, &camera.get_rotation_viewmatrix());
+            self.shader.set_mat4("projection", false, projection);
+
+            self.gl.active_texture(glow::TEXTURE0);
+            cubemap.bind();
+            self.shader.set_int("skybox", 0);
+
+            self.mesh.draw();
+
+            self.gl.depth_func(glow::LESS);
+        }
+    }
+}
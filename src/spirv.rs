@@ -0,0 +1,258 @@
+use thiserror::Error;
+
+use crate::shader::ShaderType;
+
+const MAGIC_NUMBER: u32 = 0x0723_0203;
+const HEADER_WORDS: usize = 5;
+
+const OP_CAPABILITY: u32 = 17;
+const OP_ENTRY_POINT: u32 = 15;
+const OP_DECORATE: u32 = 71;
+
+const CAPABILITY_SHADER: u32 = 1;
+const DECORATION_SPEC_ID: u32 = 1;
+
+const EXECUTION_MODEL_VERTEX: u32 = 0;
+const EXECUTION_MODEL_FRAGMENT: u32 = 4;
+
+#[derive(Error, Debug)]
+pub enum SpirvError {
+    #[error("SPIR-V length of {length} bytes is not a multiple of 4")]
+    InvalidLength { length: usize },
+
+    #[error("Module is too short to hold a SPIR-V header")]
+    TruncatedHeader,
+
+    #[error("Invalid SPIR-V magic number: {magic:#010x}")]
+    InvalidMagicNumber { magic: u32 },
+
+    #[error("Unsupported SPIR-V version {major}.{minor}")]
+    UnsupportedVersion { major: u32, minor: u32 },
+
+    #[error("Malformed instruction at word {offset}")]
+    MalformedInstruction { offset: usize },
+
+    #[error("Module does not declare the Shader capability")]
+    MissingShaderCapability,
+
+    #[error("No {stage:?} entry point named {name:?}")]
+    MissingEntryPoint { name: String, stage: ShaderType },
+
+    #[error("Module has no specialization constant with SpecId {id}")]
+    UnknownSpecializationConstant { id: u32 },
+}
+
+struct EntryPoint {
+    execution_model: u32,
+    name: String,
+}
+
+/// A SPIR-V module that passed header and instruction stream validation.
+pub struct SpirvModule {
+    words: Vec<u32>,
+    entry_points: Vec<EntryPoint>,
+    specialization_ids: Vec<u32>,
+}
+
+/// Entry point and specialization constant values used when specializing a SPIR-V shader.
+///
+/// Constants are addressed by their `SpecId` decoration, `layout(constant_id = N)` in GLSL.
+pub struct Specialization {
+    entry_point: String,
+    constants: Vec<(u32, u32)>,
+}
+
+impl SpirvModule {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SpirvError> {
+        let chunks = bytes.chunks_exact(4);
+        if !chunks.remainder().is_empty() {
+            return Err(SpirvError::InvalidLength {
+                length: bytes.len(),
+            });
+        }
+
+        let words = chunks
+            .map(|chunk| u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect();
+
+        Self::from_words(words)
+    }
+
+    pub fn from_words(mut words: Vec<u32>) -> Result<Self, SpirvError> {
+        if words.len() < HEADER_WORDS {
+            return Err(SpirvError::TruncatedHeader);
+        }
+
+        // Modules written on a machine with the other endianness
+        if words[0] == MAGIC_NUMBER.swap_bytes() {
+            words.iter_mut().for_each(|word| *word = word.swap_bytes());
+        }
+
+        if words[0] != MAGIC_NUMBER {
+            return Err(SpirvError::InvalidMagicNumber { magic: words[0] });
+        }
+
+        let major = (words[1] >> 16) & 0xff;
+        let minor = (words[1] >> 8) & 0xff;
+        if major != 1 {
+            return Err(SpirvError::UnsupportedVersion { major, minor });
+        }
+
+        let mut has_shader_capability = false;
+        let mut entry_points = Vec::new();
+        let mut specialization_ids = Vec::new();
+
+        let mut offset = HEADER_WORDS;
+        while offset < words.len() {
+            let word_count = (words[offset] >> 16) as usize;
+            let opcode = words[offset] & 0xffff;
+
+            if word_count == 0 || offset + word_count > words.len() {
+                return Err(SpirvError::MalformedInstruction { offset });
+            }
+
+            let operands = &words[offset + 1..offset + word_count];
+            match opcode {
+                OP_CAPABILITY => {
+                    has_shader_capability |= operands.first() == Some(&CAPABILITY_SHADER);
+                }
+                OP_ENTRY_POINT if operands.len() >= 3 => {
+                    let name = Self::parse_string(&operands[2..])
+                        .ok_or(SpirvError::MalformedInstruction { offset })?;
+                    entry_points.push(EntryPoint {
+                        execution_model: operands[0],
+                        name,
+                    });
+                }
+                OP_ENTRY_POINT => return Err(SpirvError::MalformedInstruction { offset }),
+                OP_DECORATE if operands.get(1) == Some(&DECORATION_SPEC_ID) => {
+                    let id = operands
+                        .get(2)
+                        .ok_or(SpirvError::MalformedInstruction { offset })?;
+                    specialization_ids.push(*id);
+                }
+                _ => {}
+            }
+
+            offset += word_count;
+        }
+
+        if !has_shader_capability {
+            return Err(SpirvError::MissingShaderCapability);
+        }
+
+        Ok(SpirvModule {
+            words,
+            entry_points,
+            specialization_ids,
+        })
+    }
+
+    pub fn words(&self) -> &[u32] {
+        &self.words
+    }
+
+    pub fn has_entry_point(&self, name: &str, stage: ShaderType) -> bool {
+        let execution_model = match stage {
+            ShaderType::VertexShader => EXECUTION_MODEL_VERTEX,
+            ShaderType::FragmentShader => EXECUTION_MODEL_FRAGMENT,
+        };
+
+        self.entry_points
+            .iter()
+            .any(|entry| entry.execution_model == execution_model && entry.name == name)
+    }
+
+    pub fn specialization_ids(&self) -> &[u32] {
+        &self.specialization_ids
+    }
+
+    /// Checks that `specialization` can be applied to this module for the given stage.
+    pub fn validate(
+        &self,
+        stage: ShaderType,
+        specialization: &Specialization,
+    ) -> Result<(), SpirvError> {
+        if !self.has_entry_point(&specialization.entry_point, stage) {
+            return Err(SpirvError::MissingEntryPoint {
+                name: specialization.entry_point.clone(),
+                stage,
+            });
+        }
+
+        match specialization
+            .constants
+            .iter()
+            .find(|(id, _)| !self.specialization_ids.contains(id))
+        {
+            Some((id, _)) => Err(SpirvError::UnknownSpecializationConstant { id: *id }),
+            None => Ok(()),
+        }
+    }
+
+    /// Literal strings are nul-terminated UTF-8 packed little end first into words.
+    fn parse_string(words: &[u32]) -> Option<String> {
+        let bytes: Vec<u8> = words
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .take_while(|byte| *byte != 0)
+            .collect();
+
+        if bytes.len() == words.len() * 4 {
+            // Missing nul terminator
+            return None;
+        }
+
+        String::from_utf8(bytes).ok()
+    }
+}
+
+impl Specialization {
+    pub fn new(entry_point: &str) -> Self {
+        Specialization {
+            entry_point: entry_point.to_string(),
+            constants: Vec::new(),
+        }
+    }
+
+    pub fn entry_point(&self) -> &str {
+        &self.entry_point
+    }
+
+    pub fn constants(&self) -> &[(u32, u32)] {
+        &self.constants
+    }
+
+    pub fn set_bool(&mut self, id: u32, value: bool) {
+        self.set_bits(id, value as u32)
+    }
+
+    pub fn set_int(&mut self, id: u32, value: i32) {
+        self.set_bits(id, value as u32)
+    }
+
+    pub fn set_uint(&mut self, id: u32, value: u32) {
+        self.set_bits(id, value)
+    }
+
+    pub fn set_float(&mut self, id: u32, value: f32) {
+        self.set_bits(id, value.to_bits())
+    }
+
+    fn set_bits(&mut self, id: u32, bits: u32) {
+        match self
+            .constants
+            .iter_mut()
+            .find(|(existing, _)| *existing == id)
+        {
+            Some((_, value)) => *value = bits,
+            None => self.constants.push((id, bits)),
+        }
+    }
+}
+
+impl Default for Specialization {
+    fn default() -> Self {
+        Specialization::new("main")
+    }
+}
@@ -9,9 +9,51 @@ use glow::HasContext;
 use image::DynamicImage;
 use thiserror::Error;
 
+mod animated;
+mod array;
+mod atlas;
+mod cache;
+mod compressed;
+mod cubemap;
+mod decompress;
+mod format;
+mod loader;
+mod meta;
+mod mipmap;
+mod normal_map;
+mod procedural;
+mod readback;
+mod sampler;
+mod streaming;
+
+pub use animated::{load_frames, AnimatedTexture, FrameTimer};
+pub use array::{Texture3D, TextureArray};
+pub use atlas::{AtlasLayout, AtlasOptions, AtlasRect, TextureAtlas, UvRect};
+pub use cache::{TextureCache, TextureMemory};
+pub use compressed::{CompressedFormat, CompressedImage, ContainerError};
+pub use cubemap::{equirectangular_to_faces, Cubemap, CubemapLayout};
+use format::with_row_alignment;
+pub use format::{ColorSpace, TextureFormat};
+pub use loader::{LoadStatus, TextureHandle, TextureLoader};
+pub use meta::TextureMeta;
+pub use mipmap::{generate_mipmaps, MipFilter, Mipmaps};
+pub use normal_map::{normal_map_from_height, NormalFilter};
+pub use procedural::{
+    checkerboard, gradient, missing_image, noise, solid, uv_grid, GradientDirection, Noise,
+};
+pub use readback::ExportError;
+pub use sampler::{Filter, Sampler, SamplerDesc, WrapMode};
+pub use streaming::{TextureStreamer, UpdateError};
+
 pub struct Texture {
     gl: Rc<glow::Context>,
     texture: glow::NativeTexture,
+    width: u32,
+    height: u32,
+    format: TextureFormat,
+    compressed: Option<CompressedFormat>,
+    levels: u32,
+    flipped: bool,
 }
 
 #[derive(Error, Debug)]
@@ -29,102 +71,655 @@ pub enum CreationError {
         io_error: io::Error,
     },
 
+    #[error("Unrecognized image format: {error}")]
+    UnknownImageFormat { error: image::ImageError },
+
     #[error("Failed to load image: {error}")]
     ImageLoadingFailed {
-        path: PathBuf,
+        path: Option<PathBuf>,
         extension: image::ImageFormat,
         error: image::ImageError,
     },
 
+    #[error("Expected {expected} bytes of pixel data, got {actual}")]
+    PixelDataSizeMismatch { expected: usize, actual: usize },
+
+    #[error("Cubemap face {face} is {found:?}, expected {expected:?}")]
+    CubemapFaceMismatch {
+        face: usize,
+        expected: (u32, u32),
+        found: (u32, u32),
+    },
+
+    #[error("A {width}x{height} image is not a cubemap cross or strip layout")]
+    UnknownCubemapLayout { width: u32, height: u32 },
+
+    #[error("A texture array or volume needs at least one layer")]
+    NoLayers,
+
+    #[error("Layer {layer} is {found:?}, expected {expected:?}")]
+    LayerSizeMismatch {
+        layer: usize,
+        expected: (u32, u32),
+        found: (u32, u32),
+    },
+
+    #[error("Layer {layer} has color type {found:?}, expected {expected:?}")]
+    LayerFormatMismatch {
+        layer: usize,
+        expected: image::ColorType,
+        found: image::ColorType,
+    },
+
+    #[error("A {width}x{height} image can't be split into {columns}x{rows} slices")]
+    InvalidSliceGrid {
+        width: u32,
+        height: u32,
+        columns: u32,
+        rows: u32,
+    },
+
+    #[error("3D textures can't have CPU generated mipmaps")]
+    CpuMipmapsUnsupported,
+
+    #[error("Atlas entry {name:?} was added twice")]
+    DuplicateAtlasEntry { name: String },
+
+    #[error("Images don't fit in a {max_size}x{max_size} atlas")]
+    AtlasTooSmall { max_size: u32 },
+
+    #[error("Failed to parse texture metadata {path:?}: {error}")]
+    MetaParsingFailed {
+        path: PathBuf,
+        error: toml::de::Error,
+    },
+
+    #[error("Failed to read texture container: {error}")]
+    ContainerParsingFailed {
+        path: Option<PathBuf>,
+        error: ContainerError,
+    },
+
+    #[error("{format:?} is not supported by the context and can't be decompressed")]
+    CompressedFormatUnsupported { format: CompressedFormat },
+
     #[error("Failed to create texture: {error_message}")]
     TextureCreationFailed {
-        path: PathBuf,
-        extension: image::ImageFormat,
+        path: Option<PathBuf>,
+        extension: Option<image::ImageFormat>,
         error_message: String,
     },
 }
 
+/// Layout of raw pixel data passed to [`Texture::from_pixels`], 8 bits per channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    R8,
+    Rg8,
+    Rgb8,
+    Rgba8,
+}
+
+/// An image file decoded on the CPU, waiting to be uploaded.
+pub(crate) enum DecodedImage {
+    Image {
+        image: DynamicImage,
+        format: image::ImageFormat,
+    },
+    Compressed(CompressedImage),
+}
+
+/// Settings applied when a texture is created.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureOptions {
+    pub sampler: SamplerDesc,
+    /// Whether color values are sRGB encoded, picks an sRGB internal format for 8-bit images
+    pub color_space: ColorSpace,
+    /// Overrides the internal format [`TextureFormat::for_color_type`] picks from the image
+    pub format: Option<TextureFormat>,
+    /// Flips decoded images so their bottom row is at `t = 0`, as OpenGL expects. On by default.
+    ///
+    /// Turn it off for images that are already bottom row first and for formats such as glTF
+    /// that put `t = 0` at the top. Raw pixels, compressed containers and cubemaps are never
+    /// flipped.
+    pub flip_vertically: bool,
+    /// How lower levels are made, by the driver by default.
+    ///
+    /// Compressed containers keep the levels they hold unless this is [`Mipmaps::None`], and
+    /// 3D textures can't use [`Mipmaps::Cpu`].
+    pub mipmaps: Mipmaps,
+}
+
+impl Default for TextureOptions {
+    fn default() -> Self {
+        TextureOptions {
+            sampler: SamplerDesc::default(),
+            color_space: ColorSpace::default(),
+            format: None,
+            flip_vertically: true,
+            mipmaps: Mipmaps::default(),
+        }
+    }
+}
+
+impl TextureOptions {
+    /// [`TextureOptions::sampler`], minifying without mipmaps when there are none.
+    pub(crate) fn sampler_desc(&self) -> SamplerDesc {
+        match self.mipmaps {
+            Mipmaps::None => SamplerDesc {
+                mipmap_filter: None,
+                ..self.sampler
+            },
+            _ => self.sampler,
+        }
+    }
+
+    /// Applies [`TextureOptions::flip_vertically`] to a decoded image.
+    pub(crate) fn orient(&self, image: DynamicImage) -> DynamicImage {
+        if self.flip_vertically {
+            image.flipv()
+        } else {
+            image
+        }
+    }
+
+    /// The internal format for an image of `color`, honouring [`TextureOptions::format`].
+    pub(crate) fn format_for(&self, color: image::ColorType) -> TextureFormat {
+        self.format
+            .unwrap_or_else(|| TextureFormat::for_color_type(color, self.color_space))
+    }
+}
+
 impl Texture {
+    /// Loads a texture with [`TextureOptions::default`].
     pub fn new(gl: Rc<glow::Context>, path: &Path) -> Result<Self, CreationError> {
-        let format = match path.extension() {
-            Some(ext) => match image::ImageFormat::from_extension(ext) {
-                Some(format) => format,
-                None => {
-                    return Err(CreationError::UnknownFileExtension {
-                        path: PathBuf::from(path),
-                        extension: ext.to_os_string(),
+        Self::with_options(gl, path, &TextureOptions::default())
+    }
+
+    /// Loads a texture from an image file, the format is picked from the file extension.
+    ///
+    /// Radiance `.hdr` and OpenEXR `.exr` files decode to 32-bit floats and are stored as
+    /// `Rgb32F`/`Rgba32F`, set [`TextureOptions::format`] to a 16-bit float format to halve that.
+    ///
+    /// `.ktx2` and `.dds` containers are loaded with [`Texture::from_compressed`]. Settings in a
+    /// [`TextureMeta`] sidecar next to the file override `options`.
+    pub fn with_options(
+        gl: Rc<glow::Context>,
+        path: &Path,
+        options: &TextureOptions,
+    ) -> Result<Self, CreationError> {
+        let (decoded, options) = Self::decode(path, options)?;
+        Self::from_decoded(gl, decoded, path, &options)
+    }
+
+    /// The CPU side of [`Texture::with_options`]: applies the sidecar, then reads, decodes and
+    /// orients the file. Returns the options to upload with.
+    ///
+    /// This does not touch the context, so it can run on any thread.
+    pub(crate) fn decode(
+        path: &Path,
+        options: &TextureOptions,
+    ) -> Result<(DecodedImage, TextureOptions), CreationError> {
+        match TextureMeta::load(path)? {
+            Some(meta) => {
+                let options = meta.apply(options);
+                let path = meta.compressed.as_deref().unwrap_or(path);
+                Ok((Self::decode_file(path, &options)?, options))
+            }
+            None => Ok((Self::decode_file(path, options)?, options.clone())),
+        }
+    }
+
+    fn decode_file(path: &Path, options: &TextureOptions) -> Result<DecodedImage, CreationError> {
+        let is_container = path.extension().is_some_and(|extension| {
+            extension.eq_ignore_ascii_case("ktx2") || extension.eq_ignore_ascii_case("dds")
+        });
+        if is_container {
+            let bytes = match std::fs::read(path) {
+                Ok(bytes) => bytes,
+                Err(err) => {
+                    return Err(CreationError::FileOpenFailed {
+                        error_message: err.to_string(),
+                        file_path: PathBuf::from(path),
+                        io_error: err,
                     })
                 }
-            },
-            None => {
-                return Err(CreationError::NoFileExtension {
-                    path: PathBuf::from(path),
-                })
-            }
-        };
+            };
 
-        let file = match std::fs::File::open(path) {
-            Ok(file) => file,
-            Err(err) => {
-                return Err(CreationError::FileOpenFailed {
-                    error_message: err.to_string(),
-                    file_path: PathBuf::from(path),
-                    io_error: err,
-                })
+            return CompressedImage::parse(&bytes, options.color_space)
+                .map(DecodedImage::Compressed)
+                .map_err(|error| CreationError::ContainerParsingFailed {
+                    path: Some(PathBuf::from(path)),
+                    error,
+                });
+        }
+
+        let (image, format) = load_image(path)?;
+        Ok(DecodedImage::Image {
+            image: options.orient(image),
+            format,
+        })
+    }
+
+    /// The GL side of [`Texture::with_options`]: uploads what [`Texture::decode`] produced.
+    pub(crate) fn from_decoded(
+        gl: Rc<glow::Context>,
+        decoded: DecodedImage,
+        path: &Path,
+        options: &TextureOptions,
+    ) -> Result<Self, CreationError> {
+        match decoded {
+            DecodedImage::Compressed(image) => Self::from_compressed(gl, &image, options),
+            DecodedImage::Image { image, format } => {
+                Self::upload(gl, &image, options, options.flip_vertically).map_err(
+                    |error_message| CreationError::TextureCreationFailed {
+                        path: Some(PathBuf::from(path)),
+                        extension: Some(format),
+                        error_message,
+                    },
+                )
             }
-        };
+        }
+    }
 
-        let buffer = BufReader::new(file);
+    /// Decodes an encoded image held in memory, such as one embedded with `include_bytes!`.
+    ///
+    /// The format is guessed from the content rather than a file extension, KTX2 and DDS
+    /// containers are loaded with [`Texture::from_compressed`].
+    pub fn from_bytes(
+        gl: Rc<glow::Context>,
+        bytes: &[u8],
+        options: &TextureOptions,
+    ) -> Result<Self, CreationError> {
+        if CompressedImage::is_container(bytes) {
+            let image = CompressedImage::parse(bytes, options.color_space)
+                .map_err(|error| CreationError::ContainerParsingFailed { path: None, error })?;
+            return Self::from_compressed(gl, &image, options);
+        }
 
-        let image = match image::load(buffer, format) {
-            Ok(image) => image.flipv(),
+        let format = match image::guess_format(bytes) {
+            Ok(format) => format,
+            Err(err) => return Err(CreationError::UnknownImageFormat { error: err }),
+        };
+
+        let image = match image::load_from_memory_with_format(bytes, format) {
+            Ok(image) => options.orient(image),
             Err(err) => {
                 return Err(CreationError::ImageLoadingFailed {
-                    path: PathBuf::from(path),
+                    path: None,
                     extension: format,
                     error: err,
                 })
             }
         };
 
+        Self::upload(gl, &image, options, options.flip_vertically).map_err(|error_message| {
+            CreationError::TextureCreationFailed {
+                path: None,
+                extension: Some(format),
+                error_message,
+            }
+        })
+    }
+
+    /// Creates a texture from raw, tightly packed pixels.
+    ///
+    /// Rows are uploaded as given, so the first row ends up at `t = 0`, whatever
+    /// [`TextureOptions::flip_vertically`] says.
+    pub fn from_pixels(
+        gl: Rc<glow::Context>,
+        width: u32,
+        height: u32,
+        format: PixelFormat,
+        data: &[u8],
+        options: &TextureOptions,
+    ) -> Result<Self, CreationError> {
+        let channels = match format {
+            PixelFormat::R8 => 1,
+            PixelFormat::Rg8 => 2,
+            PixelFormat::Rgb8 => 3,
+            PixelFormat::Rgba8 => 4,
+        };
+
+        let expected = width as usize * height as usize * channels;
+        if data.len() != expected {
+            return Err(CreationError::PixelDataSizeMismatch {
+                expected,
+                actual: data.len(),
+            });
+        }
+
+        // The length check above guarantees every buffer fits
+        let data = data.to_vec();
+        let image = match format {
+            PixelFormat::R8 => {
+                image::GrayImage::from_raw(width, height, data).map(DynamicImage::ImageLuma8)
+            }
+            PixelFormat::Rg8 => {
+                image::GrayAlphaImage::from_raw(width, height, data).map(DynamicImage::ImageLumaA8)
+            }
+            PixelFormat::Rgb8 => {
+                image::RgbImage::from_raw(width, height, data).map(DynamicImage::ImageRgb8)
+            }
+            PixelFormat::Rgba8 => {
+                image::RgbaImage::from_raw(width, height, data).map(DynamicImage::ImageRgba8)
+            }
+        }
+        .expect("Pixel data length was checked");
+
+        Self::from_image(gl, &image, options)
+    }
+
+    /// Creates a texture from an image already in memory, such as a generated one.
+    ///
+    /// Like [`Texture::from_pixels`] the first row ends up at `t = 0`, whatever
+    /// [`TextureOptions::flip_vertically`] says.
+    pub fn from_image(
+        gl: Rc<glow::Context>,
+        image: &DynamicImage,
+        options: &TextureOptions,
+    ) -> Result<Self, CreationError> {
+        Self::upload(gl, image, options, false).map_err(|error_message| {
+            CreationError::TextureCreationFailed {
+                path: None,
+                extension: None,
+                error_message,
+            }
+        })
+    }
+
+    /// Uploads a block compressed image with all of its mip levels.
+    ///
+    /// The data is uploaded as stored, top row first, and [`TextureOptions::format`] is ignored.
+    /// When the context lacks the extension for the format it is decompressed on the CPU, which
+    /// covers every format but BC6H, BC7 and ASTC. Decompressed data isn't swizzled, so one and
+    /// two channel formats sample the same as they would compressed.
+    pub fn from_compressed(
+        gl: Rc<glow::Context>,
+        image: &CompressedImage,
+        options: &TextureOptions,
+    ) -> Result<Self, CreationError> {
+        let compressed_format = image.format();
+        let color_space = image.color_space();
+
+        // Only the levels in the file exist, generate_mipmap can't fill in compressed ones
+        let level_count = match options.mipmaps {
+            Mipmaps::None => 1,
+            _ => image.levels().len(),
+        };
+
+        let decompressed = if compressed_format.is_supported(&gl, color_space) {
+            None
+        } else if compressed_format.can_decompress() {
+            let levels = (0..level_count)
+                .map(|level| image.decompress(level))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|error| CreationError::ContainerParsingFailed { path: None, error })?;
+            Some(levels)
+        } else {
+            return Err(CreationError::CompressedFormatUnsupported {
+                format: compressed_format,
+            });
+        };
+
         unsafe {
-            let texture = match gl.create_texture() {
-                Ok(texture) => texture,
-                Err(err) => {
-                    return Err(CreationError::TextureCreationFailed {
-                        path: PathBuf::from(path),
-                        extension: format,
-                        error_message: err,
-                    })
+            let texture = gl.create_texture().map_err(|error_message| {
+                CreationError::TextureCreationFailed {
+                    path: None,
+                    extension: None,
+                    error_message,
                 }
-            };
+            })?;
 
             gl.bind_texture(glow::TEXTURE_2D, Some(texture));
-
-            let image = DynamicImage::ImageRgba8(image.into_rgba8());
-
-            gl.tex_image_2d(
+            options
+                .sampler_desc()
+                .apply_to_texture(&gl, glow::TEXTURE_2D);
+            gl.tex_parameter_i32(
                 glow::TEXTURE_2D,
-                0,
-                glow::RGBA as i32, // Thanks OpenGL
-                image.width() as i32,
-                image.height() as i32,
-                0,
-                glow::RGBA,
-                glow::UNSIGNED_BYTE,
-                Some(image.as_bytes()),
+                glow::TEXTURE_MAX_LEVEL,
+                level_count as i32 - 1,
             );
 
-            gl.generate_mipmap(glow::TEXTURE_2D);
-            Ok(Texture { gl, texture })
+            let (format, compressed) = match decompressed {
+                Some(levels) => {
+                    let format = levels[0].color();
+                    let format = TextureFormat::for_color_type(format, color_space);
+                    for (level, level_image) in levels.iter().enumerate() {
+                        tex_image_2d(
+                            &gl,
+                            glow::TEXTURE_2D,
+                            level as i32,
+                            format,
+                            level_image,
+                            color_space,
+                        );
+                    }
+                    (format, None)
+                }
+                None => {
+                    let internal_format = compressed_format.internal_format(color_space);
+                    for (level, data) in image.levels()[..level_count].iter().enumerate() {
+                        let (width, height) = image.level_dimensions(level);
+                        gl.compressed_tex_image_2d(
+                            glow::TEXTURE_2D,
+                            level as i32,
+                            internal_format as i32,
+                            width as i32,
+                            height as i32,
+                            0,
+                            data.len() as i32,
+                            data,
+                        );
+                    }
+                    (
+                        compressed_format.decompressed_format(color_space),
+                        Some(compressed_format),
+                    )
+                }
+            };
+
+            Ok(Texture {
+                gl,
+                texture,
+                width: image.width(),
+                height: image.height(),
+                format,
+                compressed,
+                levels: level_count as u32,
+                flipped: false,
+            })
+        }
+    }
+
+    /// Uploads `image` as is, `flipped` records whether it was flipped after decoding.
+    fn upload(
+        gl: Rc<glow::Context>,
+        image: &DynamicImage,
+        options: &TextureOptions,
+        flipped: bool,
+    ) -> Result<Self, String> {
+        unsafe {
+            let texture = gl.create_texture()?;
+
+            gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+            options
+                .sampler_desc()
+                .apply_to_texture(&gl, glow::TEXTURE_2D);
+
+            let format = options.format_for(image.color());
+            format.apply_swizzle(&gl, glow::TEXTURE_2D);
+            tex_image_2d(&gl, glow::TEXTURE_2D, 0, format, image, options.color_space);
+
+            let levels = upload_mipmaps(&gl, glow::TEXTURE_2D, format, image, options);
+            Ok(Texture {
+                gl,
+                texture,
+                width: image.width(),
+                height: image.height(),
+                format,
+                compressed: None,
+                levels,
+                flipped,
+            })
         }
     }
 
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The internal format, or for compressed textures the format they decompress to.
+    pub fn format(&self) -> TextureFormat {
+        self.format
+    }
+
+    /// The block compressed format the texture is stored in on the GPU, if any.
+    pub fn compressed_format(&self) -> Option<CompressedFormat> {
+        self.compressed
+    }
+
+    /// Whether the image was flipped on upload, so its first row is at `t = 1`.
+    pub fn is_flipped(&self) -> bool {
+        self.flipped
+    }
+
+    /// Number of mip levels, including the full size image.
+    pub fn levels(&self) -> u32 {
+        self.levels
+    }
+
+    /// Estimated GPU memory in bytes for all mip levels, ignoring driver padding.
+    pub fn memory_size(&self) -> usize {
+        (0..self.levels)
+            .map(|level| {
+                let width = (self.width >> level).max(1);
+                let height = (self.height >> level).max(1);
+                match self.compressed {
+                    Some(format) => format.level_size(width, height),
+                    None => width as usize * height as usize * self.format.texel_size(),
+                }
+            })
+            .sum()
+    }
+
     pub fn bind(&self) {
         unsafe { self.gl.bind_texture(glow::TEXTURE_2D, Some(self.texture)) }
     }
 }
 
+/// Number of levels `generate_mipmap` creates for a `width` by `height` image.
+pub(crate) fn full_mip_chain(width: u32, height: u32) -> u32 {
+    32 - width.max(height).max(1).leading_zeros()
+}
+
+/// Fills in the levels below `image`, already uploaded as level 0 of `target`, as
+/// [`TextureOptions::mipmaps`] asks and returns the number of levels.
+pub(crate) unsafe fn upload_mipmaps(
+    gl: &glow::Context,
+    target: u32,
+    format: TextureFormat,
+    image: &DynamicImage,
+    options: &TextureOptions,
+) -> u32 {
+    match options.mipmaps {
+        Mipmaps::None => {
+            gl.tex_parameter_i32(target, glow::TEXTURE_MAX_LEVEL, 0);
+            1
+        }
+        Mipmaps::Driver => {
+            gl.generate_mipmap(target);
+            full_mip_chain(image.width(), image.height())
+        }
+        Mipmaps::Cpu(filter) => {
+            let levels = generate_mipmaps(image, filter, options.color_space);
+            for (level, level_image) in levels.iter().enumerate() {
+                tex_image_2d(
+                    gl,
+                    target,
+                    level as i32 + 1,
+                    format,
+                    level_image,
+                    options.color_space,
+                );
+            }
+            levels.len() as u32 + 1
+        }
+    }
+}
+
+/// Uploads `image` as mip `level` of `target`, converted to `format`.
+pub(crate) unsafe fn tex_image_2d(
+    gl: &glow::Context,
+    target: u32,
+    level: i32,
+    format: TextureFormat,
+    image: &DynamicImage,
+    color_space: ColorSpace,
+) {
+    let data = format.pixel_data(image, color_space);
+
+    with_row_alignment(gl, format, || {
+        gl.tex_image_2d(
+            target,
+            level,
+            format.internal_format() as i32, // Thanks OpenGL
+            image.width() as i32,
+            image.height() as i32,
+            0,
+            format.pixel_format(),
+            format.pixel_type(),
+            Some(&data),
+        )
+    });
+}
+
+/// Decodes the image at `path` as stored, top row first.
+pub(crate) fn load_image(path: &Path) -> Result<(DynamicImage, image::ImageFormat), CreationError> {
+    let format = match path.extension() {
+        Some(ext) => match image::ImageFormat::from_extension(ext) {
+            Some(format) => format,
+            None => {
+                return Err(CreationError::UnknownFileExtension {
+                    path: PathBuf::from(path),
+                    extension: ext.to_os_string(),
+                })
+            }
+        },
+        None => {
+            return Err(CreationError::NoFileExtension {
+                path: PathBuf::from(path),
+            })
+        }
+    };
+
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(err) => {
+            return Err(CreationError::FileOpenFailed {
+                error_message: err.to_string(),
+                file_path: PathBuf::from(path),
+                io_error: err,
+            })
+        }
+    };
+
+    let buffer = BufReader::new(file);
+
+    match image::load(buffer, format) {
+        Ok(image) => Ok((image, format)),
+        Err(err) => Err(CreationError::ImageLoadingFailed {
+            path: Some(PathBuf::from(path)),
+            extension: format,
+            error: err,
+        }),
+    }
+}
+
 impl Drop for Texture {
     fn drop(&mut self) {
         unsafe { self.gl.delete_texture(self.texture) }
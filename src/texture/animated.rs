@@ -0,0 +1,246 @@
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use image::{
+    codecs::{gif::GifDecoder, png::PngDecoder},
+    AnimationDecoder, DynamicImage, ImageFormat,
+};
+
+use super::{array::slice_grid, load_image, CreationError, TextureArray, TextureOptions};
+
+/// Frames at or under this many seconds are stretched to [`DEFAULT_FRAME_TIME`], as browsers
+/// do, since many files rely on it.
+const MIN_FRAME_TIME: f32 = 0.01;
+const DEFAULT_FRAME_TIME: f32 = 0.1;
+
+/// A flipbook animation, one frame per layer of a [`TextureArray`].
+///
+/// Call [`AnimatedTexture::update`] once per frame and pass [`AnimatedTexture::frame`] to the
+/// shader as the layer to sample.
+pub struct AnimatedTexture {
+    frames: TextureArray,
+    timer: FrameTimer,
+}
+
+/// Picks the frame of an animation from the time passed, without touching the context.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameTimer {
+    /// Time each frame ends at, from the start of the animation
+    ends: Vec<f32>,
+    elapsed: f32,
+    looping: bool,
+}
+
+impl AnimatedTexture {
+    /// Loads an animated GIF or APNG, or a still image as a single frame.
+    pub fn from_file(
+        gl: Rc<glow::Context>,
+        path: &Path,
+        options: &TextureOptions,
+    ) -> Result<Self, CreationError> {
+        let frames = load_frames(path)?
+            .into_iter()
+            .map(|(frame, duration)| (options.orient(frame), duration))
+            .collect();
+        Self::from_frames(gl, frames, options)
+    }
+
+    /// Loads a sprite sheet of `columns` by `rows` equal frames, read left to right, then top
+    /// to bottom, each shown for `frame_time` seconds.
+    pub fn from_sheet(
+        gl: Rc<glow::Context>,
+        path: &Path,
+        columns: u32,
+        rows: u32,
+        frame_time: f32,
+        options: &TextureOptions,
+    ) -> Result<Self, CreationError> {
+        let (image, _) = load_image(path)?;
+        let frames = slice_grid(&image, columns, rows)?
+            .into_iter()
+            .map(|frame| (options.orient(frame), frame_time))
+            .collect();
+        Self::from_frames(gl, frames, options)
+    }
+
+    /// Creates an animation from decoded frames and their durations in seconds, all frames of
+    /// the same size and color type.
+    ///
+    /// Rows are uploaded as given, so the first row of each frame ends up at `t = 0`.
+    pub fn from_frames(
+        gl: Rc<glow::Context>,
+        frames: Vec<(DynamicImage, f32)>,
+        options: &TextureOptions,
+    ) -> Result<Self, CreationError> {
+        let (images, durations): (Vec<_>, Vec<_>) = frames.into_iter().unzip();
+        let frames = TextureArray::from_images(gl, &images, options)?;
+
+        Ok(AnimatedTexture {
+            frames,
+            timer: FrameTimer::new(&durations),
+        })
+    }
+
+    /// Advances the animation by `delta_time` seconds.
+    pub fn update(&mut self, delta_time: f32) {
+        self.timer.update(delta_time)
+    }
+
+    /// The layer of [`AnimatedTexture::texture`] to show now.
+    pub fn frame(&self) -> u32 {
+        self.timer.frame() as u32
+    }
+
+    pub fn frame_count(&self) -> u32 {
+        self.frames.layers()
+    }
+
+    pub fn timer(&self) -> &FrameTimer {
+        &self.timer
+    }
+
+    pub fn timer_mut(&mut self) -> &mut FrameTimer {
+        &mut self.timer
+    }
+
+    pub fn texture(&self) -> &TextureArray {
+        &self.frames
+    }
+
+    /// Binds the frames to `TEXTURE_2D_ARRAY` of the active unit.
+    pub fn bind(&self) {
+        self.frames.bind()
+    }
+}
+
+impl FrameTimer {
+    /// A looping timer for frames lasting `durations` seconds, negative ones count as zero.
+    pub fn new(durations: &[f32]) -> Self {
+        let ends = durations
+            .iter()
+            .scan(0.0, |end, duration| {
+                *end += duration.max(0.0);
+                Some(*end)
+            })
+            .collect();
+
+        FrameTimer {
+            ends,
+            elapsed: 0.0,
+            looping: true,
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        let total = self.duration();
+        self.elapsed += delta_time.max(0.0);
+
+        if self.looping && total > 0.0 {
+            self.elapsed %= total;
+        } else {
+            self.elapsed = self.elapsed.min(total);
+        }
+    }
+
+    /// Index of the frame to show now.
+    pub fn frame(&self) -> usize {
+        let frame = self.ends.partition_point(|&end| end <= self.elapsed);
+        frame.min(self.ends.len().saturating_sub(1))
+    }
+
+    /// Length of one pass through every frame in seconds.
+    pub fn duration(&self) -> f32 {
+        self.ends.last().copied().unwrap_or(0.0)
+    }
+
+    /// Seconds into the current pass.
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    pub fn is_looping(&self) -> bool {
+        self.looping
+    }
+
+    /// Looping animations start over after the last frame, others stay on it. On by default.
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    /// Whether an animation that doesn't loop has reached its end.
+    pub fn is_finished(&self) -> bool {
+        !self.looping && self.elapsed >= self.duration()
+    }
+
+    /// Goes back to the start of the first frame.
+    pub fn restart(&mut self) {
+        self.elapsed = 0.0;
+    }
+}
+
+/// Decodes every frame of an animated GIF or APNG with its duration in seconds.
+///
+/// Frames are composed onto the full canvas, top row first. Still images, including PNGs
+/// without animation, give a single frame.
+pub fn load_frames(path: &Path) -> Result<Vec<(DynamicImage, f32)>, CreationError> {
+    let format = ImageFormat::from_path(path).ok();
+    if !matches!(format, Some(ImageFormat::Gif | ImageFormat::Png)) {
+        let (image, _) = load_image(path)?;
+        return Ok(vec![(image, DEFAULT_FRAME_TIME)]);
+    }
+
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) => {
+            return Err(CreationError::FileOpenFailed {
+                error_message: err.to_string(),
+                file_path: PathBuf::from(path),
+                io_error: err,
+            })
+        }
+    };
+    let reader = BufReader::new(file);
+
+    let loading_failed = |format, error| CreationError::ImageLoadingFailed {
+        path: Some(PathBuf::from(path)),
+        extension: format,
+        error,
+    };
+
+    let frames = if format == Some(ImageFormat::Gif) {
+        GifDecoder::new(reader)
+            .and_then(|decoder| decoder.into_frames().collect_frames())
+            .map_err(|error| loading_failed(ImageFormat::Gif, error))?
+    } else {
+        let decoder =
+            PngDecoder::new(reader).map_err(|error| loading_failed(ImageFormat::Png, error))?;
+        if !decoder.is_apng() {
+            let image = DynamicImage::from_decoder(decoder)
+                .map_err(|error| loading_failed(ImageFormat::Png, error))?;
+            return Ok(vec![(image, DEFAULT_FRAME_TIME)]);
+        }
+        decoder
+            .apng()
+            .into_frames()
+            .collect_frames()
+            .map_err(|error| loading_failed(ImageFormat::Png, error))?
+    };
+
+    Ok(frames
+        .into_iter()
+        .map(|frame| {
+            let (numerator, denominator) = frame.delay().numer_denom_ms();
+            let seconds = numerator as f32 / denominator.max(1) as f32 / 1000.0;
+            let seconds = if seconds <= MIN_FRAME_TIME {
+                DEFAULT_FRAME_TIME
+            } else {
+                seconds
+            };
+            (DynamicImage::ImageRgba8(frame.into_buffer()), seconds)
+        })
+        .collect())
+}
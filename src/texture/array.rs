@@ -0,0 +1,389 @@
+use std::{
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use glow::HasContext;
+use image::{DynamicImage, GenericImageView};
+
+use super::{
+    format::with_row_alignment, generate_mipmaps, load_image, ColorSpace, CreationError, Mipmaps,
+    TextureFormat, TextureOptions,
+};
+
+/// A `TEXTURE_2D_ARRAY`, a stack of same-sized 2D layers sampled with a layer index.
+///
+/// Layers loaded from files are oriented like [`super::Texture`], see
+/// [`TextureOptions::flip_vertically`].
+pub struct TextureArray {
+    gl: Rc<glow::Context>,
+    texture: glow::NativeTexture,
+    width: u32,
+    height: u32,
+    layers: u32,
+    format: TextureFormat,
+}
+
+/// A `TEXTURE_3D`, a volume built from 2D slices with the first slice at `r = 0`.
+///
+/// Slices loaded from files are oriented like [`super::Texture`], see
+/// [`TextureOptions::flip_vertically`].
+pub struct Texture3D {
+    gl: Rc<glow::Context>,
+    texture: glow::NativeTexture,
+    width: u32,
+    height: u32,
+    depth: u32,
+    format: TextureFormat,
+}
+
+impl TextureArray {
+    /// Loads one layer per file, all images must have the same size and color type.
+    pub fn from_files(
+        gl: Rc<glow::Context>,
+        paths: &[&Path],
+        options: &TextureOptions,
+    ) -> Result<Self, CreationError> {
+        let (layers, format) = load_layers(paths, options)?;
+
+        Self::from_images(gl, &layers, options).map_err(|error| match error {
+            CreationError::TextureCreationFailed { error_message, .. } => {
+                CreationError::TextureCreationFailed {
+                    path: Some(PathBuf::from(paths[0])),
+                    extension: Some(format),
+                    error_message,
+                }
+            }
+            error => error,
+        })
+    }
+
+    /// Creates an array from decoded images, all of the same size and color type.
+    ///
+    /// Rows are uploaded as given, so the first row of each image ends up at `t = 0`.
+    pub fn from_images(
+        gl: Rc<glow::Context>,
+        images: &[DynamicImage],
+        options: &TextureOptions,
+    ) -> Result<Self, CreationError> {
+        validate_layers(images)?;
+
+        let format = options.format_for(images[0].color());
+        let texture = upload_layers(&gl, glow::TEXTURE_2D_ARRAY, images, format, options).map_err(
+            |error_message| CreationError::TextureCreationFailed {
+                path: None,
+                extension: None,
+                error_message,
+            },
+        )?;
+
+        Ok(TextureArray {
+            gl,
+            texture,
+            width: images[0].width(),
+            height: images[0].height(),
+            layers: images.len() as u32,
+            format,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn layers(&self) -> u32 {
+        self.layers
+    }
+
+    pub fn format(&self) -> TextureFormat {
+        self.format
+    }
+
+    pub fn bind(&self) {
+        unsafe {
+            self.gl
+                .bind_texture(glow::TEXTURE_2D_ARRAY, Some(self.texture))
+        }
+    }
+}
+
+impl Texture3D {
+    /// Loads one slice per file, all images must have the same size and color type.
+    pub fn from_files(
+        gl: Rc<glow::Context>,
+        paths: &[&Path],
+        options: &TextureOptions,
+    ) -> Result<Self, CreationError> {
+        let (slices, format) = load_layers(paths, options)?;
+
+        Self::from_images(gl, &slices, options).map_err(|error| match error {
+            CreationError::TextureCreationFailed { error_message, .. } => {
+                CreationError::TextureCreationFailed {
+                    path: Some(PathBuf::from(paths[0])),
+                    extension: Some(format),
+                    error_message,
+                }
+            }
+            error => error,
+        })
+    }
+
+    /// Loads a volume stored as a `columns` by `rows` grid of slices in a single image.
+    ///
+    /// Slices are read left to right, then top to bottom.
+    pub fn from_sliced(
+        gl: Rc<glow::Context>,
+        path: &Path,
+        columns: u32,
+        rows: u32,
+        options: &TextureOptions,
+    ) -> Result<Self, CreationError> {
+        let (image, format) = load_image(path)?;
+        let slices: Vec<_> = slice_grid(&image, columns, rows)?
+            .into_iter()
+            .map(|slice| options.orient(slice))
+            .collect();
+
+        Self::from_images(gl, &slices, options).map_err(|error| match error {
+            CreationError::TextureCreationFailed { error_message, .. } => {
+                CreationError::TextureCreationFailed {
+                    path: Some(PathBuf::from(path)),
+                    extension: Some(format),
+                    error_message,
+                }
+            }
+            error => error,
+        })
+    }
+
+    /// Creates a volume from decoded slices, all of the same size and color type.
+    ///
+    /// Rows are uploaded as given, so the first row of each slice ends up at `t = 0`. Mip levels
+    /// can only come from the driver, [`Mipmaps::Cpu`] is an error.
+    pub fn from_images(
+        gl: Rc<glow::Context>,
+        images: &[DynamicImage],
+        options: &TextureOptions,
+    ) -> Result<Self, CreationError> {
+        validate_layers(images)?;
+        if let Mipmaps::Cpu(_) = options.mipmaps {
+            return Err(CreationError::CpuMipmapsUnsupported);
+        }
+
+        let format = options.format_for(images[0].color());
+        let texture = upload_layers(&gl, glow::TEXTURE_3D, images, format, options).map_err(
+            |error_message| CreationError::TextureCreationFailed {
+                path: None,
+                extension: None,
+                error_message,
+            },
+        )?;
+
+        Ok(Texture3D {
+            gl,
+            texture,
+            width: images[0].width(),
+            height: images[0].height(),
+            depth: images.len() as u32,
+            format,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    pub fn format(&self) -> TextureFormat {
+        self.format
+    }
+
+    pub fn bind(&self) {
+        unsafe { self.gl.bind_texture(glow::TEXTURE_3D, Some(self.texture)) }
+    }
+}
+
+impl Drop for TextureArray {
+    fn drop(&mut self) {
+        unsafe { self.gl.delete_texture(self.texture) }
+    }
+}
+
+impl Drop for Texture3D {
+    fn drop(&mut self) {
+        unsafe { self.gl.delete_texture(self.texture) }
+    }
+}
+
+/// Splits `image` into a `columns` by `rows` grid of equal cells, read left to right, then top
+/// to bottom.
+pub(crate) fn slice_grid(
+    image: &DynamicImage,
+    columns: u32,
+    rows: u32,
+) -> Result<Vec<DynamicImage>, CreationError> {
+    let (width, height) = image.dimensions();
+
+    let (cell_width, cell_height) = match (width.checked_div(columns), height.checked_div(rows)) {
+        (Some(cell_width), Some(cell_height))
+            if cell_width * columns == width && cell_height * rows == height =>
+        {
+            (cell_width, cell_height)
+        }
+        _ => {
+            return Err(CreationError::InvalidSliceGrid {
+                width,
+                height,
+                columns,
+                rows,
+            })
+        }
+    };
+
+    Ok((0..rows)
+        .flat_map(|row| (0..columns).map(move |column| (column, row)))
+        .map(|(column, row)| {
+            image.crop_imm(
+                column * cell_width,
+                row * cell_height,
+                cell_width,
+                cell_height,
+            )
+        })
+        .collect())
+}
+
+/// Loads and orients every file, returning the format of the first one.
+fn load_layers(
+    paths: &[&Path],
+    options: &TextureOptions,
+) -> Result<(Vec<DynamicImage>, image::ImageFormat), CreationError> {
+    let mut layers = Vec::with_capacity(paths.len());
+    let mut first_format = None;
+
+    for path in paths {
+        let (image, format) = load_image(path)?;
+        first_format.get_or_insert(format);
+        layers.push(options.orient(image));
+    }
+
+    match first_format {
+        Some(format) => Ok((layers, format)),
+        None => Err(CreationError::NoLayers),
+    }
+}
+
+/// Checks that there is at least one layer and every layer matches the first.
+fn validate_layers(images: &[DynamicImage]) -> Result<(), CreationError> {
+    let first = images.first().ok_or(CreationError::NoLayers)?;
+
+    for (layer, image) in images.iter().enumerate() {
+        if image.dimensions() != first.dimensions() {
+            return Err(CreationError::LayerSizeMismatch {
+                layer,
+                expected: first.dimensions(),
+                found: image.dimensions(),
+            });
+        }
+
+        if image.color() != first.color() {
+            return Err(CreationError::LayerFormatMismatch {
+                layer,
+                expected: first.color(),
+                found: image.color(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Uploads `images` as the layers of a `TEXTURE_2D_ARRAY` or `TEXTURE_3D`.
+///
+/// With [`Mipmaps::Cpu`] every layer of an array is filtered on its own. Volumes shrink in depth
+/// too, so callers reject it for them.
+fn upload_layers(
+    gl: &glow::Context,
+    target: u32,
+    images: &[DynamicImage],
+    format: TextureFormat,
+    options: &TextureOptions,
+) -> Result<glow::NativeTexture, String> {
+    unsafe {
+        let texture = gl.create_texture()?;
+
+        gl.bind_texture(target, Some(texture));
+        options.sampler_desc().apply_to_texture(gl, target);
+        format.apply_swizzle(gl, target);
+
+        tex_image_3d(gl, target, 0, format, images, options.color_space);
+
+        match options.mipmaps {
+            Mipmaps::None => gl.tex_parameter_i32(target, glow::TEXTURE_MAX_LEVEL, 0),
+            Mipmaps::Driver => gl.generate_mipmap(target),
+            Mipmaps::Cpu(filter) => {
+                let layer_levels: Vec<_> = images
+                    .iter()
+                    .map(|image| generate_mipmaps(image, filter, options.color_space))
+                    .collect();
+
+                for level in 0..layer_levels[0].len() {
+                    let layers: Vec<_> = layer_levels
+                        .iter()
+                        .map(|levels| levels[level].clone())
+                        .collect();
+                    tex_image_3d(
+                        gl,
+                        target,
+                        level as i32 + 1,
+                        format,
+                        &layers,
+                        options.color_space,
+                    );
+                }
+            }
+        }
+        Ok(texture)
+    }
+}
+
+/// Uploads same-sized `images` as mip `level` of every layer of `target`.
+unsafe fn tex_image_3d(
+    gl: &glow::Context,
+    target: u32,
+    level: i32,
+    format: TextureFormat,
+    images: &[DynamicImage],
+    color_space: ColorSpace,
+) {
+    let data: Vec<u8> = images
+        .iter()
+        .flat_map(|image| format.pixel_data(image, color_space))
+        .collect();
+
+    with_row_alignment(gl, format, || {
+        gl.tex_image_3d(
+            target,
+            level,
+            format.internal_format() as i32,
+            images[0].width() as i32,
+            images[0].height() as i32,
+            images.len() as i32,
+            0,
+            format.pixel_format(),
+            format.pixel_type(),
+            Some(&data),
+        )
+    });
+}
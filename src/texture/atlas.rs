@@ -0,0 +1,342 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::Path,
+    rc::Rc,
+};
+
+use image::{DynamicImage, RgbaImage};
+
+use super::{load_image, CreationError, Texture, TextureOptions};
+
+/// Many small images packed into one texture, so they can be drawn without rebinding.
+pub struct TextureAtlas {
+    texture: Rc<Texture>,
+    layout: AtlasLayout,
+}
+
+/// Where [`AtlasLayout::pack`] put every image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AtlasLayout {
+    width: u32,
+    height: u32,
+    padding: u32,
+    rects: BTreeMap<String, AtlasRect>,
+}
+
+/// An image in the atlas in pixels, without its padding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AtlasRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// An image in the atlas in texture coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UvRect {
+    pub min: glam::Vec2,
+    pub max: glam::Vec2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AtlasOptions {
+    /// Pixels between images, filled with their repeated edges so filtering doesn't pick up
+    /// neighbours
+    pub padding: u32,
+    /// Largest width and height to grow the atlas to
+    pub max_size: u32,
+}
+
+impl Default for AtlasOptions {
+    fn default() -> Self {
+        AtlasOptions {
+            padding: 1,
+            max_size: 4096,
+        }
+    }
+}
+
+impl TextureAtlas {
+    /// Packs one image per file, each named after its file stem.
+    ///
+    /// Images are oriented like [`Texture`], see [`TextureOptions::flip_vertically`], so each
+    /// one keeps its file's look between the corners of its [`UvRect`].
+    pub fn from_files(
+        gl: Rc<glow::Context>,
+        paths: &[&Path],
+        atlas_options: &AtlasOptions,
+        options: &TextureOptions,
+    ) -> Result<Self, CreationError> {
+        let names: Vec<_> = paths
+            .iter()
+            .map(|path| path.file_stem().unwrap_or_default().to_string_lossy())
+            .collect();
+
+        let mut images = Vec::with_capacity(paths.len());
+        for (path, name) in paths.iter().zip(&names) {
+            let (image, _) = load_image(path)?;
+            images.push((name.as_ref(), options.orient(image)));
+        }
+
+        Self::from_images(gl, &images, atlas_options, options)
+    }
+
+    /// Packs named images, rows are copied as given so the first row of each image ends up at
+    /// the `min.y` of its [`UvRect`].
+    pub fn from_images(
+        gl: Rc<glow::Context>,
+        images: &[(&str, DynamicImage)],
+        atlas_options: &AtlasOptions,
+        options: &TextureOptions,
+    ) -> Result<Self, CreationError> {
+        let sizes: Vec<_> = images
+            .iter()
+            .map(|(name, image)| (*name, image.width(), image.height()))
+            .collect();
+        let layout = AtlasLayout::pack(&sizes, atlas_options)?;
+
+        let atlas = layout.compose(images);
+        let texture = Texture::from_image(gl, &atlas, options)?;
+
+        Ok(TextureAtlas {
+            texture: Rc::new(texture),
+            layout,
+        })
+    }
+
+    pub fn texture(&self) -> &Rc<Texture> {
+        &self.texture
+    }
+
+    pub fn layout(&self) -> &AtlasLayout {
+        &self.layout
+    }
+
+    /// Texture coordinates of the image called `name`.
+    pub fn uv_rect(&self, name: &str) -> Option<UvRect> {
+        self.layout.uv_rect(name)
+    }
+
+    /// Binds the atlas texture to `TEXTURE_2D` of the active unit.
+    pub fn bind(&self) {
+        self.texture.bind()
+    }
+}
+
+impl AtlasLayout {
+    /// Packs `(name, width, height)` rectangles with the skyline bottom-left heuristic.
+    ///
+    /// The atlas starts at the smallest power of two square that could hold everything and
+    /// doubles its shorter side until everything fits. Rectangles are placed tallest first, with
+    /// ties broken by width and then name, so the same set always packs the same way whatever
+    /// order it is passed in.
+    pub fn pack(sizes: &[(&str, u32, u32)], options: &AtlasOptions) -> Result<Self, CreationError> {
+        let mut names = HashSet::new();
+        if let Some(&(name, ..)) = sizes.iter().find(|(name, ..)| !names.insert(*name)) {
+            return Err(CreationError::DuplicateAtlasEntry {
+                name: name.to_string(),
+            });
+        }
+
+        let padded = |size: u32| size.saturating_add(options.padding.saturating_mul(2));
+        let mut order: Vec<_> = sizes.to_vec();
+        order.sort_by(|a, b| b.2.cmp(&a.2).then(b.1.cmp(&a.1)).then(a.0.cmp(b.0)));
+
+        let area: u64 = order
+            .iter()
+            .map(|&(_, width, height)| padded(width) as u64 * padded(height) as u64)
+            .sum();
+        let widest = order.iter().map(|&(_, width, _)| padded(width)).max();
+        let tallest = order.iter().map(|&(_, _, height)| padded(height)).max();
+
+        let side = ((area as f64).sqrt().ceil() as u32)
+            .max(widest.unwrap_or(1))
+            .max(tallest.unwrap_or(1))
+            .max(1)
+            .checked_next_power_of_two()
+            .unwrap_or(u32::MAX);
+        let (mut width, mut height) = (side, side);
+
+        while width <= options.max_size && height <= options.max_size {
+            let mut skyline = Skyline::new(width, height);
+            let placed: Option<BTreeMap<_, _>> = order
+                .iter()
+                .map(|&(name, rect_width, rect_height)| {
+                    let (x, y) = skyline.insert(padded(rect_width), padded(rect_height))?;
+                    let rect = AtlasRect {
+                        x: x + options.padding,
+                        y: y + options.padding,
+                        width: rect_width,
+                        height: rect_height,
+                    };
+                    Some((name.to_string(), rect))
+                })
+                .collect();
+
+            if let Some(rects) = placed {
+                return Ok(AtlasLayout {
+                    width,
+                    height,
+                    padding: options.padding,
+                    rects,
+                });
+            }
+
+            if width <= height {
+                width = width.saturating_mul(2);
+            } else {
+                height = height.saturating_mul(2);
+            }
+        }
+
+        Err(CreationError::AtlasTooSmall {
+            max_size: options.max_size,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Packed images by name, in name order.
+    pub fn rects(&self) -> &BTreeMap<String, AtlasRect> {
+        &self.rects
+    }
+
+    pub fn rect(&self, name: &str) -> Option<AtlasRect> {
+        self.rects.get(name).copied()
+    }
+
+    /// Texture coordinates of the outer edges of the image called `name`, with `min` at its
+    /// first row and column.
+    pub fn uv_rect(&self, name: &str) -> Option<UvRect> {
+        let rect = self.rects.get(name)?;
+        let size = glam::vec2(self.width as f32, self.height as f32);
+        Some(UvRect {
+            min: glam::vec2(rect.x as f32, rect.y as f32) / size,
+            max: glam::vec2((rect.x + rect.width) as f32, (rect.y + rect.height) as f32) / size,
+        })
+    }
+
+    /// Copies `images` into an `Rgba8` atlas image, filling the padding around each with its
+    /// edges. Images missing from the layout are skipped.
+    pub fn compose(&self, images: &[(&str, DynamicImage)]) -> DynamicImage {
+        let padding = self.padding;
+        let mut atlas = RgbaImage::new(self.width, self.height);
+
+        for (name, image) in images {
+            let Some(rect) = self.rects.get(*name) else {
+                continue;
+            };
+            let image = image.to_rgba8();
+            if image.width() == 0 || image.height() == 0 {
+                continue;
+            }
+
+            let left = rect.x.saturating_sub(padding);
+            let top = rect.y.saturating_sub(padding);
+            let right = (rect.x + rect.width + padding).min(self.width);
+            let bottom = (rect.y + rect.height + padding).min(self.height);
+            for y in top..bottom {
+                for x in left..right {
+                    let source_x = x.clamp(rect.x, rect.x + rect.width - 1) - rect.x;
+                    let source_y = y.clamp(rect.y, rect.y + rect.height - 1) - rect.y;
+                    atlas.put_pixel(x, y, *image.get_pixel(source_x, source_y));
+                }
+            }
+        }
+
+        DynamicImage::ImageRgba8(atlas)
+    }
+}
+
+/// The top edge of everything placed so far, as segments from left to right.
+struct Skyline {
+    width: u32,
+    height: u32,
+    segments: Vec<Segment>,
+}
+
+#[derive(Clone, Copy)]
+struct Segment {
+    x: u32,
+    y: u32,
+    width: u32,
+}
+
+impl Skyline {
+    fn new(width: u32, height: u32) -> Self {
+        Skyline {
+            width,
+            height,
+            segments: vec![Segment { x: 0, y: 0, width }],
+        }
+    }
+
+    /// Places a rectangle where its top ends lowest, the leftmost of equal spots.
+    fn insert(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let (index, y) = (0..self.segments.len())
+            .filter_map(|index| Some((index, self.fits(index, width, height)?)))
+            .min_by_key(|&(index, y)| (y + height, self.segments[index].x))?;
+
+        let x = self.segments[index].x;
+        self.segments.insert(
+            index,
+            Segment {
+                x,
+                y: y + height,
+                width,
+            },
+        );
+
+        // Segments now under the new one shrink or go away
+        let right = x + width;
+        let next = index + 1;
+        while next < self.segments.len() && self.segments[next].x < right {
+            let segment = &mut self.segments[next];
+            let segment_right = segment.x + segment.width;
+            if segment_right <= right {
+                self.segments.remove(next);
+            } else {
+                segment.width = segment_right - right;
+                segment.x = right;
+                break;
+            }
+        }
+
+        self.segments.dedup_by(|right, left| {
+            let merge = left.y == right.y;
+            if merge {
+                left.width += right.width;
+            }
+            merge
+        });
+
+        Some((x, y))
+    }
+
+    /// The height a rectangle starting at segment `index` would rest at, if it fits.
+    fn fits(&self, index: usize, width: u32, height: u32) -> Option<u32> {
+        let x = self.segments[index].x;
+        if x.checked_add(width)? > self.width {
+            return None;
+        }
+
+        let mut y = 0;
+        let mut covered = 0;
+        for segment in &self.segments[index..] {
+            if covered >= width {
+                break;
+            }
+            y = y.max(segment.y);
+            covered += segment.width;
+        }
+
+        (y.checked_add(height)? <= self.height).then_some(y)
+    }
+}
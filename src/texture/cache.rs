@@ -0,0 +1,147 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::{Rc, Weak},
+};
+
+use super::{CreationError, Texture, TextureFormat, TextureOptions};
+
+/// Shares textures loaded from the same file.
+///
+/// Paths are canonicalized, so `res/a.png` and `./res/../res/a.png` are the same entry. The
+/// cache only holds weak references, a texture is freed once the last handle to it is dropped.
+pub struct TextureCache {
+    gl: Rc<glow::Context>,
+    entries: HashMap<PathBuf, Vec<Entry>>,
+}
+
+/// A line of [`TextureCache::memory_report`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureMemory {
+    pub path: PathBuf,
+    /// Handles held outside the cache
+    pub ref_count: usize,
+    pub width: u32,
+    pub height: u32,
+    pub format: TextureFormat,
+    /// Estimated bytes of GPU memory, see [`Texture::memory_size`]
+    pub bytes: usize,
+}
+
+/// The same file loaded with different options gives different textures.
+struct Entry {
+    options: TextureOptions,
+    texture: Weak<Texture>,
+}
+
+impl TextureCache {
+    pub fn new(gl: Rc<glow::Context>) -> Self {
+        TextureCache {
+            gl,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Returns the live texture for `path` and `options`, loading it if there is none.
+    pub fn load(
+        &mut self,
+        path: &Path,
+        options: &TextureOptions,
+    ) -> Result<Rc<Texture>, CreationError> {
+        let key = canonicalize(path)?;
+        let entries = self.entries.entry(key.clone()).or_default();
+        entries.retain(|entry| entry.texture.strong_count() > 0);
+
+        let cached = entries
+            .iter()
+            .find(|entry| entry.options == *options)
+            .and_then(|entry| entry.texture.upgrade());
+        if let Some(texture) = cached {
+            return Ok(texture);
+        }
+
+        let texture = match Texture::with_options(Rc::clone(&self.gl), path, options) {
+            Ok(texture) => Rc::new(texture),
+            Err(error) => {
+                // Don't keep an empty entry around for a file that failed to load
+                if entries.is_empty() {
+                    self.entries.remove(&key);
+                }
+                return Err(error);
+            }
+        };
+        entries.push(Entry {
+            options: options.clone(),
+            texture: Rc::downgrade(&texture),
+        });
+        Ok(texture)
+    }
+
+    /// A live texture loaded from `path` with any options, without loading it.
+    pub fn get(&self, path: &Path) -> Option<Rc<Texture>> {
+        let key = std::fs::canonicalize(path).ok()?;
+        self.entries
+            .get(&key)?
+            .iter()
+            .find_map(|entry| entry.texture.upgrade())
+    }
+
+    /// Handles to textures loaded from `path`, summed over all options.
+    pub fn ref_count(&self, path: &Path) -> usize {
+        let Ok(key) = std::fs::canonicalize(path) else {
+            return 0;
+        };
+        self.entries.get(&key).map_or(0, |entries| {
+            entries
+                .iter()
+                .map(|entry| entry.texture.strong_count())
+                .sum()
+        })
+    }
+
+    /// Forgets files whose textures have all been dropped.
+    pub fn purge(&mut self) {
+        self.entries.retain(|_, entries| {
+            entries.retain(|entry| entry.texture.strong_count() > 0);
+            !entries.is_empty()
+        });
+    }
+
+    /// Memory used by every live texture, largest first.
+    pub fn memory_report(&self) -> Vec<TextureMemory> {
+        let mut report: Vec<_> = self
+            .entries
+            .iter()
+            .flat_map(|(path, entries)| {
+                entries.iter().filter_map(move |entry| {
+                    let ref_count = entry.texture.strong_count();
+                    let texture = entry.texture.upgrade()?;
+                    Some(TextureMemory {
+                        path: path.clone(),
+                        ref_count,
+                        width: texture.width(),
+                        height: texture.height(),
+                        format: texture.format(),
+                        bytes: texture.memory_size(),
+                    })
+                })
+            })
+            .collect();
+
+        report.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+        report
+    }
+
+    /// Estimated GPU memory of every live texture in the cache.
+    pub fn total_memory(&self) -> usize {
+        self.memory_report().iter().map(|entry| entry.bytes).sum()
+    }
+}
+
+fn canonicalize(path: &Path) -> Result<PathBuf, CreationError> {
+    std::fs::canonicalize(path).map_err(|err| CreationError::FileOpenFailed {
+        error_message: err.to_string(),
+        file_path: PathBuf::from(path),
+        io_error: err,
+    })
+}
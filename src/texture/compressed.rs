@@ -0,0 +1,594 @@
+use std::io::Cursor;
+
+use image::DynamicImage;
+use thiserror::Error;
+
+use super::{decompress::decompress, full_mip_chain, ColorSpace, TextureFormat};
+
+const KTX2_MAGIC: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+const DDS_MAGIC: [u8; 4] = *b"DDS ";
+/// Size of the KTX2 header, the level index follows it.
+const KTX2_HEADER_SIZE: usize = 80;
+/// Byte offset, byte length and uncompressed byte length of a level, all `u64`.
+const KTX2_LEVEL_ENTRY_SIZE: usize = 24;
+
+/// ASTC block footprints in the order of the `COMPRESSED_RGBA_ASTC_*_KHR` enums.
+const ASTC_BLOCKS: [(u8, u8); 14] = [
+    (4, 4),
+    (5, 4),
+    (5, 5),
+    (6, 5),
+    (6, 6),
+    (8, 5),
+    (8, 6),
+    (8, 8),
+    (10, 5),
+    (10, 6),
+    (10, 8),
+    (10, 10),
+    (12, 10),
+    (12, 12),
+];
+
+/// Block compressed formats that can be uploaded without decoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompressedFormat {
+    /// DXT1 without alpha
+    Bc1,
+    /// DXT1 with one bit alpha
+    Bc1Alpha,
+    /// DXT3
+    Bc2,
+    /// DXT5
+    Bc3,
+    /// Single channel RGTC1
+    Bc4,
+    /// Two channel RGTC2, usually normal maps
+    Bc5,
+    /// Unsigned half float RGB
+    Bc6H,
+    /// Signed half float RGB
+    Bc6HSigned,
+    Bc7,
+    Etc2Rgb8,
+    Etc2Rgb8A1,
+    Etc2Rgba8,
+    EacR11,
+    EacRg11,
+    Astc {
+        block_width: u8,
+        block_height: u8,
+    },
+}
+
+/// A block compressed image and its mip chain, as stored in a KTX2 or DDS container.
+///
+/// Rows are kept as stored, top row first, since blocks can't be flipped without decoding them.
+#[derive(Debug, Clone)]
+pub struct CompressedImage {
+    format: CompressedFormat,
+    color_space: ColorSpace,
+    width: u32,
+    height: u32,
+    levels: Vec<Vec<u8>>,
+}
+
+#[derive(Error, Debug)]
+pub enum ContainerError {
+    #[error("Not a KTX2 or DDS file")]
+    UnknownContainer,
+
+    #[error("Invalid KTX2 file: {error}")]
+    Ktx2ParsingFailed { error: ktx2::ParseError },
+
+    #[error("Invalid DDS file: {error}")]
+    DdsParsingFailed { error: ddsfile::Error },
+
+    #[error("Unsupported pixel format: {format}")]
+    UnsupportedFormat { format: String },
+
+    #[error("Supercompressed KTX2 files are not supported")]
+    Supercompressed,
+
+    #[error("Expected a 2D texture, found {layers} layers, {faces} faces and a depth of {depth}")]
+    NotA2dTexture { layers: u32, faces: u32, depth: u32 },
+
+    #[error("{levels} mip levels is more than the {max} a {width}x{height} image can have")]
+    TooManyLevels {
+        levels: u32,
+        max: u32,
+        width: u32,
+        height: u32,
+    },
+
+    #[error(
+        "Mip level {level} ({length} bytes at {offset}) runs past the end of the {file_size} byte file"
+    )]
+    LevelOutOfBounds {
+        level: usize,
+        offset: u64,
+        length: u64,
+        file_size: usize,
+    },
+
+    #[error("Mip level {level} needs {expected} bytes, found {actual}")]
+    LevelSizeMismatch {
+        level: usize,
+        expected: usize,
+        actual: usize,
+    },
+
+    #[error("{format:?} can't be decompressed on the CPU")]
+    DecompressionUnsupported { format: CompressedFormat },
+}
+
+impl CompressedFormat {
+    /// Width and height of a block in pixels.
+    pub fn block_size(self) -> (u32, u32) {
+        match self {
+            CompressedFormat::Astc {
+                block_width,
+                block_height,
+            } => (block_width as u32, block_height as u32),
+            _ => (4, 4),
+        }
+    }
+
+    /// Bytes per block.
+    pub fn block_bytes(self) -> usize {
+        match self {
+            CompressedFormat::Bc1
+            | CompressedFormat::Bc1Alpha
+            | CompressedFormat::Bc4
+            | CompressedFormat::Etc2Rgb8
+            | CompressedFormat::Etc2Rgb8A1
+            | CompressedFormat::EacR11 => 8,
+            _ => 16,
+        }
+    }
+
+    /// Bytes needed for a `width` by `height` image, partial blocks at the edges count as whole.
+    pub fn level_size(self, width: u32, height: u32) -> usize {
+        let (block_width, block_height) = self.block_size();
+        let columns = width.div_ceil(block_width);
+        let rows = height.div_ceil(block_height);
+        columns as usize * rows as usize * self.block_bytes()
+    }
+
+    /// The `internal_format` argument of `compressed_tex_image_2d`.
+    ///
+    /// Formats without an sRGB variant, which hold data rather than color, ignore `color_space`.
+    pub fn internal_format(self, color_space: ColorSpace) -> u32 {
+        let srgb = color_space == ColorSpace::Srgb;
+        match self {
+            CompressedFormat::Bc1 if srgb => glow::COMPRESSED_SRGB_S3TC_DXT1_EXT,
+            CompressedFormat::Bc1 => glow::COMPRESSED_RGB_S3TC_DXT1_EXT,
+            CompressedFormat::Bc1Alpha if srgb => glow::COMPRESSED_SRGB_ALPHA_S3TC_DXT1_EXT,
+            CompressedFormat::Bc1Alpha => glow::COMPRESSED_RGBA_S3TC_DXT1_EXT,
+            CompressedFormat::Bc2 if srgb => glow::COMPRESSED_SRGB_ALPHA_S3TC_DXT3_EXT,
+            CompressedFormat::Bc2 => glow::COMPRESSED_RGBA_S3TC_DXT3_EXT,
+            CompressedFormat::Bc3 if srgb => glow::COMPRESSED_SRGB_ALPHA_S3TC_DXT5_EXT,
+            CompressedFormat::Bc3 => glow::COMPRESSED_RGBA_S3TC_DXT5_EXT,
+            CompressedFormat::Bc4 => glow::COMPRESSED_RED_RGTC1,
+            CompressedFormat::Bc5 => glow::COMPRESSED_RG_RGTC2,
+            CompressedFormat::Bc6H => glow::COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT,
+            CompressedFormat::Bc6HSigned => glow::COMPRESSED_RGB_BPTC_SIGNED_FLOAT,
+            CompressedFormat::Bc7 if srgb => glow::COMPRESSED_SRGB_ALPHA_BPTC_UNORM,
+            CompressedFormat::Bc7 => glow::COMPRESSED_RGBA_BPTC_UNORM,
+            CompressedFormat::Etc2Rgb8 if srgb => glow::COMPRESSED_SRGB8_ETC2,
+            CompressedFormat::Etc2Rgb8 => glow::COMPRESSED_RGB8_ETC2,
+            CompressedFormat::Etc2Rgb8A1 if srgb => glow::COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2,
+            CompressedFormat::Etc2Rgb8A1 => glow::COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2,
+            CompressedFormat::Etc2Rgba8 if srgb => glow::COMPRESSED_SRGB8_ALPHA8_ETC2_EAC,
+            CompressedFormat::Etc2Rgba8 => glow::COMPRESSED_RGBA8_ETC2_EAC,
+            CompressedFormat::EacR11 => glow::COMPRESSED_R11_EAC,
+            CompressedFormat::EacRg11 => glow::COMPRESSED_RG11_EAC,
+            CompressedFormat::Astc {
+                block_width,
+                block_height,
+            } => {
+                let index = ASTC_BLOCKS
+                    .iter()
+                    .position(|&block| block == (block_width, block_height))
+                    .expect("ASTC formats are only created from known block sizes")
+                    as u32;
+
+                if srgb {
+                    glow::COMPRESSED_SRGB8_ALPHA8_ASTC_4x4_KHR + index
+                } else {
+                    glow::COMPRESSED_RGBA_ASTC_4x4_KHR + index
+                }
+            }
+        }
+    }
+
+    /// The uncompressed format with the same channels and precision.
+    pub fn decompressed_format(self, color_space: ColorSpace) -> TextureFormat {
+        let srgb = color_space == ColorSpace::Srgb;
+        match self {
+            CompressedFormat::Bc1 | CompressedFormat::Etc2Rgb8 if srgb => TextureFormat::Srgb8,
+            CompressedFormat::Bc1 | CompressedFormat::Etc2Rgb8 => TextureFormat::Rgb8,
+            CompressedFormat::Bc4 | CompressedFormat::EacR11 => TextureFormat::R8,
+            CompressedFormat::Bc5 | CompressedFormat::EacRg11 => TextureFormat::Rg8,
+            CompressedFormat::Bc6H | CompressedFormat::Bc6HSigned => TextureFormat::Rgb16F,
+            _ if srgb => TextureFormat::Srgb8Alpha8,
+            _ => TextureFormat::Rgba8,
+        }
+    }
+
+    /// Whether the context can sample this format directly.
+    pub fn is_supported(self, gl: &glow::Context, color_space: ColorSpace) -> bool {
+        use glow::HasContext;
+
+        let extensions = gl.supported_extensions();
+        let version = gl.version();
+        let at_least = |major: u32, minor: u32| {
+            version.major > major || (version.major == major && version.minor >= minor)
+        };
+
+        match self {
+            CompressedFormat::Bc1
+            | CompressedFormat::Bc1Alpha
+            | CompressedFormat::Bc2
+            | CompressedFormat::Bc3 => {
+                extensions.contains("GL_EXT_texture_compression_s3tc")
+                    && (color_space == ColorSpace::Linear
+                        || extensions.contains("GL_EXT_texture_sRGB")
+                        || extensions.contains("GL_EXT_texture_compression_s3tc_srgb"))
+            }
+            CompressedFormat::Bc4 | CompressedFormat::Bc5 => {
+                at_least(3, 0) || extensions.contains("GL_ARB_texture_compression_rgtc")
+            }
+            CompressedFormat::Bc6H | CompressedFormat::Bc6HSigned | CompressedFormat::Bc7 => {
+                at_least(4, 2) || extensions.contains("GL_ARB_texture_compression_bptc")
+            }
+            CompressedFormat::Etc2Rgb8
+            | CompressedFormat::Etc2Rgb8A1
+            | CompressedFormat::Etc2Rgba8
+            | CompressedFormat::EacR11
+            | CompressedFormat::EacRg11 => {
+                at_least(4, 3) || extensions.contains("GL_ARB_ES3_compatibility")
+            }
+            CompressedFormat::Astc { .. } => {
+                extensions.contains("GL_KHR_texture_compression_astc_ldr")
+            }
+        }
+    }
+
+    /// Whether [`CompressedImage::decompress`] can decode this format, everything but BC6H, BC7
+    /// and ASTC.
+    pub fn can_decompress(self) -> bool {
+        !matches!(
+            self,
+            CompressedFormat::Bc6H
+                | CompressedFormat::Bc6HSigned
+                | CompressedFormat::Bc7
+                | CompressedFormat::Astc { .. }
+        )
+    }
+}
+
+impl CompressedImage {
+    /// Whether `bytes` start like a KTX2 or DDS file.
+    pub fn is_container(bytes: &[u8]) -> bool {
+        bytes.starts_with(&KTX2_MAGIC) || bytes.starts_with(&DDS_MAGIC)
+    }
+
+    /// Parses a KTX2 or DDS file, telling them apart by their magic number.
+    ///
+    /// `color_space` is used for legacy DDS files, which don't say whether they hold sRGB data.
+    pub fn parse(bytes: &[u8], color_space: ColorSpace) -> Result<Self, ContainerError> {
+        if bytes.starts_with(&KTX2_MAGIC) {
+            Self::from_ktx2(bytes)
+        } else if bytes.starts_with(&DDS_MAGIC) {
+            Self::from_dds(bytes, color_space)
+        } else {
+            Err(ContainerError::UnknownContainer)
+        }
+    }
+
+    /// Wraps already compressed mip levels, the full size image first, checking their sizes.
+    pub fn new(
+        format: CompressedFormat,
+        color_space: ColorSpace,
+        width: u32,
+        height: u32,
+        levels: &[&[u8]],
+    ) -> Result<Self, ContainerError> {
+        check_level_count(width, height, levels.len() as u32)?;
+        Self::from_levels(format, color_space, width, height, levels.iter().copied())
+    }
+
+    pub fn from_ktx2(bytes: &[u8]) -> Result<Self, ContainerError> {
+        check_ktx2_level_index(bytes)?;
+        let reader = ktx2::Reader::new(bytes)
+            .map_err(|error| ContainerError::Ktx2ParsingFailed { error })?;
+        let header = reader.header();
+
+        if header.supercompression_scheme.is_some() {
+            return Err(ContainerError::Supercompressed);
+        }
+
+        if header.layer_count > 1 || header.face_count != 1 || header.pixel_depth > 1 {
+            return Err(ContainerError::NotA2dTexture {
+                layers: header.layer_count,
+                faces: header.face_count,
+                depth: header.pixel_depth,
+            });
+        }
+
+        let (format, color_space) = match header.format {
+            Some(format) => ktx2_format(format)?,
+            None => {
+                return Err(ContainerError::UnsupportedFormat {
+                    format: String::from("VK_FORMAT_UNDEFINED"),
+                })
+            }
+        };
+
+        let (width, height) = (header.pixel_width, header.pixel_height.max(1));
+        check_level_count(width, height, header.level_count)?;
+
+        Self::from_levels(format, color_space, width, height, reader.levels())
+    }
+
+    /// Parses a DDS file, `color_space` is used when it has no DX10 header.
+    pub fn from_dds(bytes: &[u8], color_space: ColorSpace) -> Result<Self, ContainerError> {
+        let dds = ddsfile::Dds::read(Cursor::new(bytes))
+            .map_err(|error| ContainerError::DdsParsingFailed { error })?;
+
+        if dds.get_num_array_layers() != 1 || dds.get_depth() > 1 {
+            return Err(ContainerError::NotA2dTexture {
+                layers: dds.get_num_array_layers(),
+                faces: 1,
+                depth: dds.get_depth(),
+            });
+        }
+
+        // Only the DX10 header knows about sRGB, legacy FourCC codes are taken as given
+        let (format, color_space) = match &dds.header10 {
+            Some(header10) => dxgi_format(header10.dxgi_format)?,
+            None => match dds.get_d3d_format() {
+                Some(ddsfile::D3DFormat::DXT1) => (CompressedFormat::Bc1, color_space),
+                Some(ddsfile::D3DFormat::DXT3) => (CompressedFormat::Bc2, color_space),
+                Some(ddsfile::D3DFormat::DXT5) => (CompressedFormat::Bc3, color_space),
+                Some(format) => {
+                    return Err(ContainerError::UnsupportedFormat {
+                        format: format!("{format:?}"),
+                    })
+                }
+                None => match dds.get_dxgi_format() {
+                    Some(format) => (dxgi_format(format)?.0, ColorSpace::Linear),
+                    None => {
+                        return Err(ContainerError::UnsupportedFormat {
+                            format: format!("{:?}", dds.header.spf),
+                        })
+                    }
+                },
+            },
+        };
+
+        let (width, height) = (dds.get_width(), dds.get_height());
+        let level_count = dds.get_num_mipmap_levels().max(1);
+        check_level_count(width, height, level_count)?;
+
+        // Levels follow each other tightly, smallest last
+        let mut offset = 0;
+        let levels = (0..level_count as usize).map(|level| {
+            let size = format.level_size(mip_size(width, level), mip_size(height, level));
+            let start = offset.min(dds.data.len());
+            offset += size;
+            &dds.data[start..offset.min(dds.data.len())]
+        });
+
+        Self::from_levels(format, color_space, width, height, levels)
+    }
+
+    fn from_levels<'a>(
+        format: CompressedFormat,
+        color_space: ColorSpace,
+        width: u32,
+        height: u32,
+        levels: impl Iterator<Item = &'a [u8]>,
+    ) -> Result<Self, ContainerError> {
+        let levels = levels
+            .enumerate()
+            .map(|(level, data)| {
+                let expected = format.level_size(mip_size(width, level), mip_size(height, level));
+                if data.len() < expected {
+                    return Err(ContainerError::LevelSizeMismatch {
+                        level,
+                        expected,
+                        actual: data.len(),
+                    });
+                }
+
+                Ok(data[..expected].to_vec())
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(CompressedImage {
+            format,
+            color_space,
+            width,
+            height,
+            levels,
+        })
+    }
+
+    pub fn format(&self) -> CompressedFormat {
+        self.format
+    }
+
+    pub fn color_space(&self) -> ColorSpace {
+        self.color_space
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Compressed data of every mip level, the full size image first.
+    pub fn levels(&self) -> &[Vec<u8>] {
+        &self.levels
+    }
+
+    pub fn level_dimensions(&self, level: usize) -> (u32, u32) {
+        (mip_size(self.width, level), mip_size(self.height, level))
+    }
+
+    /// Decodes mip level `level` on the CPU, see [`CompressedFormat::can_decompress`].
+    ///
+    /// Color formats give `Rgb8` or `Rgba8` images. BC4 and R11 EAC give `L8`, BC5 and RG11 EAC
+    /// give `La8` with the second channel as alpha. Panics if `level` is out of range.
+    pub fn decompress(&self, level: usize) -> Result<DynamicImage, ContainerError> {
+        let (width, height) = self.level_dimensions(level);
+        decompress(self.format, &self.levels[level], width, height).ok_or(
+            ContainerError::DecompressionUnsupported {
+                format: self.format,
+            },
+        )
+    }
+}
+
+/// Rejects mip chains longer than halving the larger side down to one pixel allows.
+fn check_level_count(width: u32, height: u32, levels: u32) -> Result<(), ContainerError> {
+    let max = full_mip_chain(width, height);
+    if levels > max {
+        return Err(ContainerError::TooManyLevels {
+            levels,
+            max,
+            width,
+            height,
+        });
+    }
+    Ok(())
+}
+
+/// Rejects KTX2 levels that don't fit in the file.
+///
+/// `ktx2::Reader` only checks the level stored last and panics slicing any other one, so every
+/// entry is checked before it gets to see the file. Files too short for their header or level
+/// index are left to the reader to report.
+fn check_ktx2_level_index(bytes: &[u8]) -> Result<(), ContainerError> {
+    let read_u32 = |at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+    let read_u64 = |at: usize| u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap());
+
+    if bytes.len() < KTX2_HEADER_SIZE {
+        return Ok(());
+    }
+    // levelCount, 0 means a single level the loader should generate the rest for
+    let level_count = read_u32(40).max(1) as usize;
+
+    for level in 0..level_count {
+        let entry = KTX2_HEADER_SIZE + level * KTX2_LEVEL_ENTRY_SIZE;
+        if entry + KTX2_LEVEL_ENTRY_SIZE > bytes.len() {
+            return Ok(());
+        }
+
+        let (offset, length) = (read_u64(entry), read_u64(entry + 8));
+        let in_bounds = offset
+            .checked_add(length)
+            .is_some_and(|end| end <= bytes.len() as u64);
+        if !in_bounds {
+            return Err(ContainerError::LevelOutOfBounds {
+                level,
+                offset,
+                length,
+                file_size: bytes.len(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn mip_size(size: u32, level: usize) -> u32 {
+    u32::try_from(level)
+        .ok()
+        .and_then(|level| size.checked_shr(level))
+        .unwrap_or(0)
+        .max(1)
+}
+
+fn ktx2_format(format: ktx2::Format) -> Result<(CompressedFormat, ColorSpace), ContainerError> {
+    use ktx2::Format;
+    use ColorSpace::{Linear, Srgb};
+
+    let astc_formats = Format::ASTC_4x4_UNORM_BLOCK.0.get()..=Format::ASTC_12x12_SRGB_BLOCK.0.get();
+    if astc_formats.contains(&format.0.get()) {
+        let index = (format.0.get() - astc_formats.start()) as usize;
+        let (block_width, block_height) = ASTC_BLOCKS[index / 2];
+        let color_space = if index % 2 == 1 { Srgb } else { Linear };
+
+        return Ok((
+            CompressedFormat::Astc {
+                block_width,
+                block_height,
+            },
+            color_space,
+        ));
+    }
+
+    Ok(match format {
+        Format::BC1_RGB_UNORM_BLOCK => (CompressedFormat::Bc1, Linear),
+        Format::BC1_RGB_SRGB_BLOCK => (CompressedFormat::Bc1, Srgb),
+        Format::BC1_RGBA_UNORM_BLOCK => (CompressedFormat::Bc1Alpha, Linear),
+        Format::BC1_RGBA_SRGB_BLOCK => (CompressedFormat::Bc1Alpha, Srgb),
+        Format::BC2_UNORM_BLOCK => (CompressedFormat::Bc2, Linear),
+        Format::BC2_SRGB_BLOCK => (CompressedFormat::Bc2, Srgb),
+        Format::BC3_UNORM_BLOCK => (CompressedFormat::Bc3, Linear),
+        Format::BC3_SRGB_BLOCK => (CompressedFormat::Bc3, Srgb),
+        Format::BC4_UNORM_BLOCK => (CompressedFormat::Bc4, Linear),
+        Format::BC5_UNORM_BLOCK => (CompressedFormat::Bc5, Linear),
+        Format::BC6H_UFLOAT_BLOCK => (CompressedFormat::Bc6H, Linear),
+        Format::BC6H_SFLOAT_BLOCK => (CompressedFormat::Bc6HSigned, Linear),
+        Format::BC7_UNORM_BLOCK => (CompressedFormat::Bc7, Linear),
+        Format::BC7_SRGB_BLOCK => (CompressedFormat::Bc7, Srgb),
+        Format::ETC2_R8G8B8_UNORM_BLOCK => (CompressedFormat::Etc2Rgb8, Linear),
+        Format::ETC2_R8G8B8_SRGB_BLOCK => (CompressedFormat::Etc2Rgb8, Srgb),
+        Format::ETC2_R8G8B8A1_UNORM_BLOCK => (CompressedFormat::Etc2Rgb8A1, Linear),
+        Format::ETC2_R8G8B8A1_SRGB_BLOCK => (CompressedFormat::Etc2Rgb8A1, Srgb),
+        Format::ETC2_R8G8B8A8_UNORM_BLOCK => (CompressedFormat::Etc2Rgba8, Linear),
+        Format::ETC2_R8G8B8A8_SRGB_BLOCK => (CompressedFormat::Etc2Rgba8, Srgb),
+        Format::EAC_R11_UNORM_BLOCK => (CompressedFormat::EacR11, Linear),
+        Format::EAC_R11G11_UNORM_BLOCK => (CompressedFormat::EacRg11, Linear),
+        format => {
+            return Err(ContainerError::UnsupportedFormat {
+                format: format!("{format:?}"),
+            })
+        }
+    })
+}
+
+fn dxgi_format(
+    format: ddsfile::DxgiFormat,
+) -> Result<(CompressedFormat, ColorSpace), ContainerError> {
+    use ddsfile::DxgiFormat;
+    use ColorSpace::{Linear, Srgb};
+
+    Ok(match format {
+        DxgiFormat::BC1_UNorm => (CompressedFormat::Bc1Alpha, Linear),
+        DxgiFormat::BC1_UNorm_sRGB => (CompressedFormat::Bc1Alpha, Srgb),
+        DxgiFormat::BC2_UNorm => (CompressedFormat::Bc2, Linear),
+        DxgiFormat::BC2_UNorm_sRGB => (CompressedFormat::Bc2, Srgb),
+        DxgiFormat::BC3_UNorm => (CompressedFormat::Bc3, Linear),
+        DxgiFormat::BC3_UNorm_sRGB => (CompressedFormat::Bc3, Srgb),
+        DxgiFormat::BC4_UNorm => (CompressedFormat::Bc4, Linear),
+        DxgiFormat::BC5_UNorm => (CompressedFormat::Bc5, Linear),
+        DxgiFormat::BC6H_UF16 => (CompressedFormat::Bc6H, Linear),
+        DxgiFormat::BC6H_SF16 => (CompressedFormat::Bc6HSigned, Linear),
+        DxgiFormat::BC7_UNorm => (CompressedFormat::Bc7, Linear),
+        DxgiFormat::BC7_UNorm_sRGB => (CompressedFormat::Bc7, Srgb),
+        format => {
+            return Err(ContainerError::UnsupportedFormat {
+                format: format!("{format:?}"),
+            })
+        }
+    })
+}
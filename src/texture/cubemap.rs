@@ -0,0 +1,318 @@
+use std::{
+    f32::consts::PI,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use glow::HasContext;
+use image::{DynamicImage, GenericImageView, Rgba32FImage};
+
+use super::{
+    generate_mipmaps, load_image, tex_image_2d, CreationError, Mipmaps, TextureFormat,
+    TextureOptions,
+};
+
+/// A `TEXTURE_CUBE_MAP` with six square faces.
+///
+/// Faces are always given in `TEXTURE_CUBE_MAP_POSITIVE_X` order (+X, -X, +Y, -Y, +Z, -Z) and,
+/// unlike [`super::Texture`], are not flipped since cubemaps use a top row first convention.
+pub struct Cubemap {
+    gl: Rc<glow::Context>,
+    texture: glow::NativeTexture,
+    face_size: u32,
+    format: TextureFormat,
+}
+
+/// Arrangements of all six faces in a single image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CubemapLayout {
+    /// 6:1, faces left to right in +X, -X, +Y, -Y, +Z, -Z order
+    HorizontalStrip,
+    /// 1:6, faces top to bottom in +X, -X, +Y, -Y, +Z, -Z order
+    VerticalStrip,
+    /// 4:3, the row `-X +Z +X -Z` with +Y above and -Y below +Z
+    HorizontalCross,
+    /// 3:4, the row `-X +Z +X` with +Y above +Z and -Y, then an upside down -Z, below it
+    VerticalCross,
+}
+
+impl Cubemap {
+    /// Loads an equirectangular (longitude/latitude) panorama, such as an `.hdr` environment map,
+    /// and projects it onto the six faces.
+    pub fn from_equirectangular(
+        gl: Rc<glow::Context>,
+        path: &Path,
+        face_size: u32,
+        options: &TextureOptions,
+    ) -> Result<Self, CreationError> {
+        let (image, format) = load_image(path)?;
+
+        let texture_format = options.format_for(image.color());
+        let faces = equirectangular_to_faces(&image, face_size).map(DynamicImage::ImageRgba32F);
+
+        Self::upload(gl, &faces, texture_format, options).map_err(|error_message| {
+            CreationError::TextureCreationFailed {
+                path: Some(PathBuf::from(path)),
+                extension: Some(format),
+                error_message,
+            }
+        })
+    }
+
+    /// Loads six separate square face images of equal size.
+    pub fn from_faces(
+        gl: Rc<glow::Context>,
+        paths: [&Path; 6],
+        options: &TextureOptions,
+    ) -> Result<Self, CreationError> {
+        let mut faces = Vec::with_capacity(6);
+        for path in paths {
+            faces.push(load_image(path)?);
+        }
+
+        let (first, format) = &faces[0];
+        let expected = (first.width(), first.width());
+        for (face, (image, _)) in faces.iter().enumerate() {
+            if image.dimensions() != expected {
+                return Err(CreationError::CubemapFaceMismatch {
+                    face,
+                    expected,
+                    found: image.dimensions(),
+                });
+            }
+        }
+
+        let format = *format;
+        let texture_format = options.format_for(first.color());
+        let faces = [0, 1, 2, 3, 4, 5].map(|face| faces[face].0.clone());
+
+        Self::upload(gl, &faces, texture_format, options).map_err(|error_message| {
+            CreationError::TextureCreationFailed {
+                path: Some(PathBuf::from(paths[0])),
+                extension: Some(format),
+                error_message,
+            }
+        })
+    }
+
+    /// Loads a single image holding all six faces, detecting the layout from its aspect ratio
+    /// when `layout` is `None`.
+    pub fn from_layout(
+        gl: Rc<glow::Context>,
+        path: &Path,
+        layout: Option<CubemapLayout>,
+        options: &TextureOptions,
+    ) -> Result<Self, CreationError> {
+        let (image, format) = load_image(path)?;
+        let (width, height) = image.dimensions();
+
+        let layout = match layout.or_else(|| CubemapLayout::detect(width, height)) {
+            Some(layout) if layout.fits(width, height) => layout,
+            _ => return Err(CreationError::UnknownCubemapLayout { width, height }),
+        };
+
+        let texture_format = options.format_for(image.color());
+        let faces = layout.extract_faces(&image);
+
+        Self::upload(gl, &faces, texture_format, options).map_err(|error_message| {
+            CreationError::TextureCreationFailed {
+                path: Some(PathBuf::from(path)),
+                extension: Some(format),
+                error_message,
+            }
+        })
+    }
+
+    /// Uploads six faces in `TEXTURE_CUBE_MAP_POSITIVE_X` order (+X, -X, +Y, -Y, +Z, -Z).
+    fn upload(
+        gl: Rc<glow::Context>,
+        faces: &[DynamicImage; 6],
+        format: TextureFormat,
+        options: &TextureOptions,
+    ) -> Result<Self, String> {
+        unsafe {
+            let texture = gl.create_texture()?;
+
+            gl.bind_texture(glow::TEXTURE_CUBE_MAP, Some(texture));
+            options
+                .sampler_desc()
+                .apply_to_texture(&gl, glow::TEXTURE_CUBE_MAP);
+            format.apply_swizzle(&gl, glow::TEXTURE_CUBE_MAP);
+
+            for (i, face) in faces.iter().enumerate() {
+                tex_image_2d(
+                    &gl,
+                    glow::TEXTURE_CUBE_MAP_POSITIVE_X + i as u32,
+                    0,
+                    format,
+                    face,
+                    options.color_space,
+                );
+            }
+
+            match options.mipmaps {
+                Mipmaps::None => {
+                    gl.tex_parameter_i32(glow::TEXTURE_CUBE_MAP, glow::TEXTURE_MAX_LEVEL, 0)
+                }
+                Mipmaps::Driver => gl.generate_mipmap(glow::TEXTURE_CUBE_MAP),
+                Mipmaps::Cpu(filter) => {
+                    for (i, face) in faces.iter().enumerate() {
+                        let levels = generate_mipmaps(face, filter, options.color_space);
+                        for (level, level_image) in levels.iter().enumerate() {
+                            tex_image_2d(
+                                &gl,
+                                glow::TEXTURE_CUBE_MAP_POSITIVE_X + i as u32,
+                                level as i32 + 1,
+                                format,
+                                level_image,
+                                options.color_space,
+                            );
+                        }
+                    }
+                }
+            }
+            Ok(Cubemap {
+                gl,
+                texture,
+                face_size: faces[0].width(),
+                format,
+            })
+        }
+    }
+
+    pub fn face_size(&self) -> u32 {
+        self.face_size
+    }
+
+    pub fn format(&self) -> TextureFormat {
+        self.format
+    }
+
+    pub fn bind(&self) {
+        unsafe {
+            self.gl
+                .bind_texture(glow::TEXTURE_CUBE_MAP, Some(self.texture))
+        }
+    }
+}
+
+impl Drop for Cubemap {
+    fn drop(&mut self) {
+        unsafe { self.gl.delete_texture(self.texture) }
+    }
+}
+
+impl CubemapLayout {
+    /// Picks the layout whose aspect ratio matches exactly.
+    pub fn detect(width: u32, height: u32) -> Option<Self> {
+        [
+            CubemapLayout::HorizontalStrip,
+            CubemapLayout::VerticalStrip,
+            CubemapLayout::HorizontalCross,
+            CubemapLayout::VerticalCross,
+        ]
+        .into_iter()
+        .find(|layout| layout.fits(width, height))
+    }
+
+    pub fn fits(self, width: u32, height: u32) -> bool {
+        width > 0
+            && match self {
+                CubemapLayout::HorizontalStrip => width == height * 6,
+                CubemapLayout::VerticalStrip => height == width * 6,
+                CubemapLayout::HorizontalCross => width * 3 == height * 4,
+                CubemapLayout::VerticalCross => width * 4 == height * 3,
+            }
+    }
+
+    /// Face size and the (column, row) cell of each face, in `TEXTURE_CUBE_MAP_POSITIVE_X` order.
+    fn cells(self, width: u32, height: u32) -> (u32, [(u32, u32); 6]) {
+        match self {
+            CubemapLayout::HorizontalStrip => {
+                (height, [(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0)])
+            }
+            CubemapLayout::VerticalStrip => {
+                (width, [(0, 0), (0, 1), (0, 2), (0, 3), (0, 4), (0, 5)])
+            }
+            CubemapLayout::HorizontalCross => {
+                (width / 4, [(2, 1), (0, 1), (1, 0), (1, 2), (1, 1), (3, 1)])
+            }
+            CubemapLayout::VerticalCross => {
+                (width / 3, [(2, 1), (0, 1), (1, 0), (1, 2), (1, 1), (1, 3)])
+            }
+        }
+    }
+
+    fn extract_faces(self, image: &DynamicImage) -> [DynamicImage; 6] {
+        let (size, cells) = self.cells(image.width(), image.height());
+
+        [0, 1, 2, 3, 4, 5].map(|face| {
+            let (column, row) = cells[face];
+            let face_image = image.crop_imm(column * size, row * size, size, size);
+
+            // The vertical cross stores -Z upside down below -Y
+            if self == CubemapLayout::VerticalCross && face == 5 {
+                face_image.rotate180()
+            } else {
+                face_image
+            }
+        })
+    }
+}
+
+/// Resamples an equirectangular panorama (top row first) into six `face_size` square faces,
+/// in `TEXTURE_CUBE_MAP_POSITIVE_X` order and orientation.
+pub fn equirectangular_to_faces(image: &DynamicImage, face_size: u32) -> [Rgba32FImage; 6] {
+    let source = image.to_rgba32f();
+
+    [0, 1, 2, 3, 4, 5].map(|face| {
+        Rgba32FImage::from_fn(face_size, face_size, |x, y| {
+            // Texel centers in [-1, 1], t grows with the row like the face's first row is t = 0
+            let sc = 2.0 * (x as f32 + 0.5) / face_size as f32 - 1.0;
+            let tc = 2.0 * (y as f32 + 0.5) / face_size as f32 - 1.0;
+
+            // Inverse of the face selection table in the GL spec
+            let direction = match face {
+                0 => glam::vec3(1.0, -tc, -sc),
+                1 => glam::vec3(-1.0, -tc, sc),
+                2 => glam::vec3(sc, 1.0, tc),
+                3 => glam::vec3(sc, -1.0, -tc),
+                4 => glam::vec3(sc, -tc, 1.0),
+                _ => glam::vec3(-sc, -tc, -1.0),
+            }
+            .normalize();
+
+            let u = 0.5 + direction.z.atan2(direction.x) / (2.0 * PI);
+            let v = 0.5 - direction.y.asin() / PI;
+
+            image::Rgba(sample_bilinear(&source, u, v))
+        })
+    })
+}
+
+/// Samples with `u` wrapping around the panorama and `v` clamped at the poles.
+fn sample_bilinear(image: &Rgba32FImage, u: f32, v: f32) -> [f32; 4] {
+    let (width, height) = image.dimensions();
+
+    let x = u * width as f32 - 0.5;
+    let y = (v * height as f32 - 0.5).clamp(0.0, (height - 1) as f32);
+
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let (fx, fy) = (x - x0, y - y0);
+
+    let column = |x: f32| (x as i64).rem_euclid(width as i64) as u32;
+    let row = |y: f32| (y as u32).min(height - 1);
+
+    let texel = |x: f32, y: f32| image.get_pixel(column(x), row(y)).0;
+    let (a, b) = (texel(x0, y0), texel(x0 + 1.0, y0));
+    let (c, d) = (texel(x0, y0 + 1.0), texel(x0 + 1.0, y0 + 1.0));
+
+    let mut result = [0.0; 4];
+    for i in 0..4 {
+        let top = a[i] + (b[i] - a[i]) * fx;
+        let bottom = c[i] + (d[i] - c[i]) * fx;
+        result[i] = top + (bottom - top) * fy;
+    }
+    result
+}
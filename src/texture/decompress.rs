@@ -0,0 +1,441 @@
+use image::{DynamicImage, GrayAlphaImage, GrayImage, RgbImage, RgbaImage};
+
+use super::CompressedFormat;
+
+/// Pixels of one 4x4 block, row by row.
+type Block = [[u8; 4]; 16];
+
+/// Modifiers of the ETC1 and ETC2 individual and differential modes, as `[a, b, -a, -b]`.
+const ETC_MODIFIERS: [[i32; 4]; 8] = [
+    [2, 8, -2, -8],
+    [5, 17, -5, -17],
+    [9, 29, -9, -29],
+    [13, 42, -13, -42],
+    [18, 60, -18, -60],
+    [24, 80, -24, -80],
+    [33, 106, -33, -106],
+    [47, 183, -47, -183],
+];
+
+/// Distances between the paint colors of the ETC2 T and H modes.
+const ETC_DISTANCES: [i32; 8] = [3, 6, 11, 16, 23, 32, 41, 64];
+
+const EAC_MODIFIERS: [[i32; 8]; 16] = [
+    [-3, -6, -9, -15, 2, 5, 8, 14],
+    [-3, -7, -10, -13, 2, 6, 9, 12],
+    [-2, -5, -8, -13, 1, 4, 7, 12],
+    [-2, -4, -6, -13, 1, 3, 5, 12],
+    [-3, -6, -8, -12, 2, 5, 7, 11],
+    [-3, -7, -9, -11, 2, 6, 8, 10],
+    [-4, -7, -8, -11, 3, 6, 7, 10],
+    [-3, -5, -8, -11, 2, 4, 7, 10],
+    [-2, -6, -8, -10, 1, 5, 7, 9],
+    [-2, -5, -8, -10, 1, 4, 7, 9],
+    [-2, -4, -8, -10, 1, 3, 7, 9],
+    [-2, -5, -7, -10, 1, 4, 6, 9],
+    [-3, -4, -7, -10, 2, 3, 6, 9],
+    [-1, -2, -3, -10, 0, 1, 2, 9],
+    [-4, -6, -8, -9, 3, 5, 7, 8],
+    [-3, -5, -7, -9, 2, 4, 6, 8],
+];
+
+/// Decodes a `width` by `height` level of `format` held in `data`, `None` for formats without
+/// a decoder.
+///
+/// Images keep the channels of the format. One channel formats give `L8` and two channel ones
+/// `La8`, with the second channel as alpha, the layout [`super::TextureFormat`] uploads them in.
+pub(crate) fn decompress(
+    format: CompressedFormat,
+    data: &[u8],
+    width: u32,
+    height: u32,
+) -> Option<DynamicImage> {
+    let decode_block: fn(&[u8]) -> Block = match format {
+        CompressedFormat::Bc1 | CompressedFormat::Bc1Alpha => |block| bc1(block, true),
+        CompressedFormat::Bc2 => bc2,
+        CompressedFormat::Bc3 => bc3,
+        CompressedFormat::Bc4 => bc4,
+        CompressedFormat::Bc5 => bc5,
+        CompressedFormat::Etc2Rgb8 => |block| etc2(block, false),
+        CompressedFormat::Etc2Rgb8A1 => |block| etc2(block, true),
+        CompressedFormat::Etc2Rgba8 => etc2_eac,
+        CompressedFormat::EacR11 => eac_r11,
+        CompressedFormat::EacRg11 => eac_rg11,
+        CompressedFormat::Bc6H
+        | CompressedFormat::Bc6HSigned
+        | CompressedFormat::Bc7
+        | CompressedFormat::Astc { .. } => return None,
+    };
+
+    let pixels = decode_blocks(format, data, width, height, decode_block);
+    let channels = |keep: &[usize]| -> Vec<u8> {
+        pixels
+            .chunks_exact(4)
+            .flat_map(|pixel| keep.iter().map(move |&channel| pixel[channel]))
+            .collect()
+    };
+
+    let image = match format {
+        CompressedFormat::Bc1 | CompressedFormat::Etc2Rgb8 => {
+            DynamicImage::ImageRgb8(RgbImage::from_raw(width, height, channels(&[0, 1, 2]))?)
+        }
+        CompressedFormat::Bc4 | CompressedFormat::EacR11 => {
+            DynamicImage::ImageLuma8(GrayImage::from_raw(width, height, channels(&[0]))?)
+        }
+        CompressedFormat::Bc5 | CompressedFormat::EacRg11 => {
+            DynamicImage::ImageLumaA8(GrayAlphaImage::from_raw(width, height, channels(&[0, 1]))?)
+        }
+        _ => DynamicImage::ImageRgba8(RgbaImage::from_raw(width, height, pixels)?),
+    };
+    Some(image)
+}
+
+/// Decodes blocks stored left to right, then top to bottom, into RGBA rows, cropping the parts
+/// of edge blocks outside the image.
+fn decode_blocks(
+    format: CompressedFormat,
+    data: &[u8],
+    width: u32,
+    height: u32,
+    decode_block: fn(&[u8]) -> Block,
+) -> Vec<u8> {
+    let (width, height) = (width as usize, height as usize);
+    let columns = width.div_ceil(4);
+    let mut pixels = vec![0; width * height * 4];
+
+    for (index, block) in data.chunks_exact(format.block_bytes()).enumerate() {
+        let (block_x, block_y) = (index % columns * 4, index / columns * 4);
+        if block_y >= height {
+            break;
+        }
+
+        for (i, pixel) in decode_block(block).iter().enumerate() {
+            let (x, y) = (block_x + i % 4, block_y + i / 4);
+            if x < width && y < height {
+                let offset = (y * width + x) * 4;
+                pixels[offset..offset + 4].copy_from_slice(pixel);
+            }
+        }
+    }
+
+    pixels
+}
+
+fn bc1(block: &[u8], three_color_mode: bool) -> Block {
+    let color0 = u16::from_le_bytes([block[0], block[1]]);
+    let color1 = u16::from_le_bytes([block[2], block[3]]);
+    let indices = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+
+    let (c0, c1) = (rgb565(color0), rgb565(color1));
+    let mix = |a: u8, b: u8, wa: u32, wb: u32| ((a as u32 * wa + b as u32 * wb) / (wa + wb)) as u8;
+    let blend = |wa, wb| {
+        [
+            mix(c0[0], c1[0], wa, wb),
+            mix(c0[1], c1[1], wa, wb),
+            mix(c0[2], c1[2], wa, wb),
+            255,
+        ]
+    };
+
+    // BC2 and BC3 always use four colors, BC1 switches to three and transparent black when
+    // the endpoints are in ascending order
+    let palette = if !three_color_mode || color0 > color1 {
+        [c0, c1, blend(2, 1), blend(1, 2)]
+    } else {
+        [c0, c1, blend(1, 1), [0, 0, 0, 0]]
+    };
+
+    std::array::from_fn(|i| palette[(indices >> (i * 2)) as usize & 3])
+}
+
+fn bc2(block: &[u8]) -> Block {
+    let alpha = u64::from_le_bytes(block[..8].try_into().unwrap());
+    let mut pixels = bc1(&block[8..], false);
+    for (i, pixel) in pixels.iter_mut().enumerate() {
+        pixel[3] = ((alpha >> (i * 4)) & 0xf) as u8 * 17;
+    }
+    pixels
+}
+
+fn bc3(block: &[u8]) -> Block {
+    let alpha = bc4_channel(&block[..8]);
+    let mut pixels = bc1(&block[8..], false);
+    for (pixel, alpha) in pixels.iter_mut().zip(alpha) {
+        pixel[3] = alpha;
+    }
+    pixels
+}
+
+fn bc4(block: &[u8]) -> Block {
+    bc4_channel(block).map(|red| [red, 0, 0, 255])
+}
+
+fn bc5(block: &[u8]) -> Block {
+    let red = bc4_channel(&block[..8]);
+    let green = bc4_channel(&block[8..]);
+    std::array::from_fn(|i| [red[i], green[i], 0, 255])
+}
+
+/// One channel of BC3 alpha, BC4 or BC5, two endpoints and 3-bit indices.
+fn bc4_channel(block: &[u8]) -> [u8; 16] {
+    let (a0, a1) = (block[0] as u32, block[1] as u32);
+    let mut bits = [0; 8];
+    bits[..6].copy_from_slice(&block[2..8]);
+    let indices = u64::from_le_bytes(bits);
+
+    let palette: [u8; 8] = std::array::from_fn(|i| {
+        let i = i as u32;
+        match i {
+            0 => a0 as u8,
+            1 => a1 as u8,
+            _ if a0 > a1 => ((a0 * (8 - i) + a1 * (i - 1)) / 7) as u8,
+            6 => 0,
+            7 => 255,
+            _ => ((a0 * (6 - i) + a1 * (i - 1)) / 5) as u8,
+        }
+    });
+
+    std::array::from_fn(|i| palette[(indices >> (i * 3)) as usize & 7])
+}
+
+fn rgb565(color: u16) -> [u8; 4] {
+    let r = (color >> 11) as u8 & 0x1f;
+    let g = (color >> 5) as u8 & 0x3f;
+    let b = color as u8 & 0x1f;
+    [
+        (r << 3) | (r >> 2),
+        (g << 2) | (g >> 4),
+        (b << 3) | (b >> 2),
+        255,
+    ]
+}
+
+/// An ETC2 RGB block, with `punchthrough` the one bit alpha variant.
+///
+/// ETC1 blocks are ETC2 blocks that never use the T, H and planar modes.
+fn etc2(block: &[u8], punchthrough: bool) -> Block {
+    let indices = u32::from_be_bytes([block[4], block[5], block[6], block[7]]);
+    // Pixels are numbered down the columns, the high bit of each index is 16 bits above the low
+    let index = |x: usize, y: usize| {
+        let bit = x * 4 + y;
+        ((indices >> (bit + 15)) & 2 | (indices >> bit) & 1) as usize
+    };
+
+    // The punchthrough variant has no individual mode, the bit says whether the block is opaque
+    let differential = punchthrough || block[3] & 2 != 0;
+    let opaque = !punchthrough || block[3] & 2 != 0;
+    let transparent = |i: usize| !opaque && i == 2;
+
+    let base = |byte: u8| (byte >> 3) as i32;
+    let delta = |byte: u8| ((byte & 7) as i32 ^ 4) - 4;
+    let overflows = |byte: u8| !(0..32).contains(&(base(byte) + delta(byte)));
+
+    if differential && overflows(block[0]) {
+        return etc2_t_or_h(block, index, transparent, true);
+    }
+    if differential && overflows(block[1]) {
+        return etc2_t_or_h(block, index, transparent, false);
+    }
+    if differential && overflows(block[2]) {
+        return etc2_planar(block);
+    }
+
+    let colors = if differential {
+        let first = [0, 1, 2].map(|c| extend_5(base(block[c]) as u8));
+        let second = [0, 1, 2].map(|c| extend_5((base(block[c]) + delta(block[c])) as u8));
+        [first, second]
+    } else {
+        [
+            [0, 1, 2].map(|c| extend_4(block[c] >> 4)),
+            [0, 1, 2].map(|c| extend_4(block[c] & 0xf)),
+        ]
+    };
+    let tables = [block[3] >> 5, (block[3] >> 2) & 7];
+    let flipped = block[3] & 1 != 0;
+
+    std::array::from_fn(|i| {
+        let (x, y) = (i % 4, i / 4);
+        let index = index(x, y);
+        if transparent(index) {
+            return [0, 0, 0, 0];
+        }
+
+        let subblock = if flipped { y >= 2 } else { x >= 2 } as usize;
+        // Without the opaque bit, the smaller modifiers become zero to leave room for alpha
+        let modifier = match ETC_MODIFIERS[tables[subblock] as usize][index] {
+            _ if !opaque && index % 2 == 0 => 0,
+            modifier => modifier,
+        };
+        let color = colors[subblock].map(|channel| clamp(channel as i32 + modifier));
+        [color[0], color[1], color[2], 255]
+    })
+}
+
+/// The T mode, picked by an overflowing red, or H mode, by green, of an ETC2 block.
+fn etc2_t_or_h(
+    block: &[u8],
+    index: impl Fn(usize, usize) -> usize,
+    transparent: impl Fn(usize) -> bool,
+    t_mode: bool,
+) -> Block {
+    let (first, second, distance) = if t_mode {
+        let first = [
+            ((block[0] >> 1) & 0xc) | (block[0] & 3),
+            block[1] >> 4,
+            block[1] & 0xf,
+        ];
+        let second = [block[2] >> 4, block[2] & 0xf, block[3] >> 4];
+        let distance = ((block[3] >> 1) & 6) | (block[3] & 1);
+        (first.map(extend_4), second.map(extend_4), distance)
+    } else {
+        let first = [
+            (block[0] >> 3) & 0xf,
+            ((block[0] & 7) << 1) | ((block[1] >> 4) & 1),
+            (block[1] & 8) | ((block[1] & 3) << 1) | (block[2] >> 7),
+        ];
+        let second = [
+            (block[2] >> 3) & 0xf,
+            ((block[2] & 7) << 1) | (block[3] >> 7),
+            (block[3] >> 3) & 0xf,
+        ];
+        let (first, second) = (first.map(extend_4), second.map(extend_4));
+
+        // The lowest distance bit is implied by which base color is larger
+        let value = |color: [u8; 3]| u32::from_be_bytes([0, color[0], color[1], color[2]]);
+        let distance =
+            (block[3] & 4) | ((block[3] & 1) << 1) | (value(first) >= value(second)) as u8;
+        (first, second, distance)
+    };
+
+    let distance = ETC_DISTANCES[distance as usize];
+    let paint = |color: [u8; 3], offset: i32| color.map(|channel| clamp(channel as i32 + offset));
+    let paints = if t_mode {
+        [
+            first,
+            paint(second, distance),
+            second,
+            paint(second, -distance),
+        ]
+    } else {
+        [
+            paint(first, distance),
+            paint(first, -distance),
+            paint(second, distance),
+            paint(second, -distance),
+        ]
+    };
+
+    std::array::from_fn(|i| {
+        let index = index(i % 4, i / 4);
+        if transparent(index) {
+            return [0, 0, 0, 0];
+        }
+        let color = paints[index];
+        [color[0], color[1], color[2], 255]
+    })
+}
+
+/// The planar mode of an ETC2 block, picked by an overflowing blue, a gradient between colors
+/// at the origin, right and bottom.
+fn etc2_planar(block: &[u8]) -> Block {
+    let b = |i: usize| block[i] as u32;
+    let extend_6 = |value: u32| ((value << 2) | (value >> 4)) as i32;
+    let extend_7 = |value: u32| ((value << 1) | (value >> 6)) as i32;
+
+    let origin = [
+        extend_6((b(0) >> 1) & 0x3f),
+        extend_7(((b(0) & 1) << 6) | ((b(1) >> 1) & 0x3f)),
+        extend_6(((b(1) & 1) << 5) | (b(2) & 0x18) | ((b(2) & 3) << 1) | (b(3) >> 7)),
+    ];
+    let horizontal = [
+        extend_6(((b(3) & 0x7c) >> 1) | (b(3) & 1)),
+        extend_7((b(4) >> 1) & 0x7f),
+        extend_6(((b(4) & 1) << 5) | ((b(5) >> 3) & 0x1f)),
+    ];
+    let vertical = [
+        extend_6(((b(5) & 7) << 3) | ((b(6) >> 5) & 7)),
+        extend_7(((b(6) & 0x1f) << 2) | ((b(7) >> 6) & 3)),
+        extend_6(b(7) & 0x3f),
+    ];
+
+    std::array::from_fn(|i| {
+        let (x, y) = ((i % 4) as i32, (i / 4) as i32);
+        let channel = |c: usize| {
+            clamp(
+                (x * (horizontal[c] - origin[c])
+                    + y * (vertical[c] - origin[c])
+                    + 4 * origin[c]
+                    + 2)
+                    >> 2,
+            )
+        };
+        [channel(0), channel(1), channel(2), 255]
+    })
+}
+
+fn etc2_eac(block: &[u8]) -> Block {
+    let alpha = eac_alpha(&block[..8]);
+    let mut pixels = etc2(&block[8..], false);
+    for (pixel, alpha) in pixels.iter_mut().zip(alpha) {
+        pixel[3] = alpha;
+    }
+    pixels
+}
+
+fn eac_r11(block: &[u8]) -> Block {
+    eac_11(block).map(|red| [red, 0, 0, 255])
+}
+
+fn eac_rg11(block: &[u8]) -> Block {
+    let red = eac_11(&block[..8]);
+    let green = eac_11(&block[8..]);
+    std::array::from_fn(|i| [red[i], green[i], 0, 255])
+}
+
+/// The 8-bit alpha of ETC2 RGBA blocks.
+fn eac_alpha(block: &[u8]) -> [u8; 16] {
+    let base = block[0] as i32;
+    let multiplier = (block[1] >> 4) as i32;
+    eac_values(block, |modifier| clamp(base + modifier * multiplier))
+}
+
+/// An unsigned 11-bit EAC channel, rounded to 8 bits.
+fn eac_11(block: &[u8]) -> [u8; 16] {
+    let base = block[0] as i32 * 8 + 4;
+    let multiplier = (block[1] >> 4) as i32;
+    eac_values(block, |modifier| {
+        // A zero multiplier still moves the value by the bare modifier
+        let offset = match multiplier {
+            0 => modifier,
+            _ => modifier * multiplier * 8,
+        };
+        let value = (base + offset).clamp(0, 2047);
+        ((value * 255 + 1023) / 2047) as u8
+    })
+}
+
+/// Pixels of an EAC block, row by row, with `value` turning each modifier into a channel.
+fn eac_values(block: &[u8], value: impl Fn(i32) -> u8) -> [u8; 16] {
+    let table = EAC_MODIFIERS[(block[1] & 0xf) as usize];
+    let mut bits = [0; 8];
+    bits[2..].copy_from_slice(&block[2..8]);
+    let indices = u64::from_be_bytes(bits);
+
+    // 3-bit indices from the top bit down, numbered down the columns
+    std::array::from_fn(|i| {
+        let bit = (i % 4) * 4 + i / 4;
+        value(table[(indices >> (45 - bit * 3)) as usize & 7])
+    })
+}
+
+fn extend_4(value: u8) -> u8 {
+    (value << 4) | value
+}
+
+fn extend_5(value: u8) -> u8 {
+    (value << 3) | (value >> 2)
+}
+
+fn clamp(value: i32) -> u8 {
+    value.clamp(0, 255) as u8
+}
@@ -0,0 +1,271 @@
+use glow::HasContext;
+use image::{ColorType, DynamicImage};
+use serde::Deserialize;
+
+/// How the stored color values should be interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorSpace {
+    /// Values are used as is, for data such as normal, specular and height maps
+    #[default]
+    Linear,
+    /// Values are sRGB encoded and decoded to linear when sampled, for color textures
+    Srgb,
+}
+
+/// Internal format of a texture on the GPU.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextureFormat {
+    R8,
+    Rg8,
+    Rgb8,
+    Rgba8,
+    Srgb8,
+    Srgb8Alpha8,
+    R16,
+    Rg16,
+    Rgb16,
+    Rgba16,
+    R16F,
+    Rg16F,
+    Rgb16F,
+    Rgba16F,
+    Rgb32F,
+    Rgba32F,
+}
+
+impl TextureFormat {
+    /// The format that keeps the channel count and precision of `color`.
+    ///
+    /// 16-bit images keep all 16 bits as normalized integers. There are no 16-bit sRGB formats,
+    /// so sRGB ones are decoded to linear half floats, which keep 11 significant bits. There is
+    /// no single or two channel sRGB format either, so sRGB grayscale images are widened to RGB.
+    /// Grayscale stays grayscale when sampled through [`TextureFormat::swizzle`].
+    pub fn for_color_type(color: ColorType, color_space: ColorSpace) -> Self {
+        match (color, color_space) {
+            (ColorType::L8, ColorSpace::Linear) => TextureFormat::R8,
+            (ColorType::La8, ColorSpace::Linear) => TextureFormat::Rg8,
+            (ColorType::Rgb8, ColorSpace::Linear) => TextureFormat::Rgb8,
+            (ColorType::L8 | ColorType::Rgb8, ColorSpace::Srgb) => TextureFormat::Srgb8,
+            (ColorType::La8 | ColorType::Rgba8, ColorSpace::Srgb) => TextureFormat::Srgb8Alpha8,
+            (ColorType::L16, ColorSpace::Linear) => TextureFormat::R16,
+            (ColorType::La16, ColorSpace::Linear) => TextureFormat::Rg16,
+            (ColorType::Rgb16, ColorSpace::Linear) => TextureFormat::Rgb16,
+            (ColorType::Rgba16, ColorSpace::Linear) => TextureFormat::Rgba16,
+            (ColorType::L16, ColorSpace::Srgb) => TextureFormat::R16F,
+            (ColorType::La16, ColorSpace::Srgb) => TextureFormat::Rg16F,
+            (ColorType::Rgb16, ColorSpace::Srgb) => TextureFormat::Rgb16F,
+            (ColorType::Rgba16, ColorSpace::Srgb) => TextureFormat::Rgba16F,
+            (ColorType::Rgb32F, _) => TextureFormat::Rgb32F,
+            (ColorType::Rgba32F, _) => TextureFormat::Rgba32F,
+            (_, ColorSpace::Linear) => TextureFormat::Rgba8,
+            (_, ColorSpace::Srgb) => TextureFormat::Srgb8Alpha8,
+        }
+    }
+
+    pub fn internal_format(self) -> u32 {
+        match self {
+            TextureFormat::R8 => glow::R8,
+            TextureFormat::Rg8 => glow::RG8,
+            TextureFormat::Rgb8 => glow::RGB8,
+            TextureFormat::Rgba8 => glow::RGBA8,
+            TextureFormat::Srgb8 => glow::SRGB8,
+            TextureFormat::Srgb8Alpha8 => glow::SRGB8_ALPHA8,
+            TextureFormat::R16 => glow::R16,
+            TextureFormat::Rg16 => glow::RG16,
+            TextureFormat::Rgb16 => glow::RGB16,
+            TextureFormat::Rgba16 => glow::RGBA16,
+            TextureFormat::R16F => glow::R16F,
+            TextureFormat::Rg16F => glow::RG16F,
+            TextureFormat::Rgb16F => glow::RGB16F,
+            TextureFormat::Rgba16F => glow::RGBA16F,
+            TextureFormat::Rgb32F => glow::RGB32F,
+            TextureFormat::Rgba32F => glow::RGBA32F,
+        }
+    }
+
+    /// `TEXTURE_SWIZZLE_RGBA` that makes one and two channel formats sample like the grayscale
+    /// and grayscale with alpha images they hold, `(l, l, l, 1)` and `(l, l, l, a)`.
+    pub fn swizzle(self) -> [u32; 4] {
+        match self.channels() {
+            1 => [glow::RED, glow::RED, glow::RED, glow::ONE],
+            2 => [glow::RED, glow::RED, glow::RED, glow::GREEN],
+            _ => [glow::RED, glow::GREEN, glow::BLUE, glow::ALPHA],
+        }
+    }
+
+    /// Sets [`TextureFormat::swizzle`] on the texture bound to `target`.
+    pub(crate) unsafe fn apply_swizzle(self, gl: &glow::Context, target: u32) {
+        let swizzle = self.swizzle().map(|channel| channel as i32);
+        gl.tex_parameter_i32_slice(target, glow::TEXTURE_SWIZZLE_RGBA, &swizzle);
+    }
+
+    /// The `format` argument of `tex_image_2d` for data from [`TextureFormat::pixel_data`].
+    pub fn pixel_format(self) -> u32 {
+        match self.channels() {
+            1 => glow::RED,
+            2 => glow::RG,
+            3 => glow::RGB,
+            _ => glow::RGBA,
+        }
+    }
+
+    /// The `type` argument of `tex_image_2d` for data from [`TextureFormat::pixel_data`].
+    pub fn pixel_type(self) -> u32 {
+        if self.is_float() {
+            glow::FLOAT
+        } else if self.is_16_bit() {
+            glow::UNSIGNED_SHORT
+        } else {
+            glow::UNSIGNED_BYTE
+        }
+    }
+
+    pub fn channels(self) -> usize {
+        match self {
+            TextureFormat::R8 | TextureFormat::R16 | TextureFormat::R16F => 1,
+            TextureFormat::Rg8 | TextureFormat::Rg16 | TextureFormat::Rg16F => 2,
+            TextureFormat::Rgb8
+            | TextureFormat::Srgb8
+            | TextureFormat::Rgb16
+            | TextureFormat::Rgb16F
+            | TextureFormat::Rgb32F => 3,
+            TextureFormat::Rgba8
+            | TextureFormat::Srgb8Alpha8
+            | TextureFormat::Rgba16
+            | TextureFormat::Rgba16F
+            | TextureFormat::Rgba32F => 4,
+        }
+    }
+
+    pub fn is_float(self) -> bool {
+        matches!(
+            self,
+            TextureFormat::R16F
+                | TextureFormat::Rg16F
+                | TextureFormat::Rgb16F
+                | TextureFormat::Rgba16F
+                | TextureFormat::Rgb32F
+                | TextureFormat::Rgba32F
+        )
+    }
+
+    /// Normalized 16-bit integer formats, uploaded and read back as `u16`.
+    pub fn is_16_bit(self) -> bool {
+        matches!(
+            self,
+            TextureFormat::R16 | TextureFormat::Rg16 | TextureFormat::Rgb16 | TextureFormat::Rgba16
+        )
+    }
+
+    pub fn is_srgb(self) -> bool {
+        matches!(self, TextureFormat::Srgb8 | TextureFormat::Srgb8Alpha8)
+    }
+
+    /// Bytes per texel on the GPU.
+    pub fn texel_size(self) -> usize {
+        match self {
+            TextureFormat::R8 => 1,
+            TextureFormat::Rg8 | TextureFormat::R16 | TextureFormat::R16F => 2,
+            TextureFormat::Rgb8 | TextureFormat::Srgb8 => 3,
+            TextureFormat::Rgba8
+            | TextureFormat::Srgb8Alpha8
+            | TextureFormat::Rg16
+            | TextureFormat::Rg16F => 4,
+            TextureFormat::Rgb16 | TextureFormat::Rgb16F => 6,
+            TextureFormat::Rgba16 | TextureFormat::Rgba16F => 8,
+            TextureFormat::Rgb32F => 12,
+            TextureFormat::Rgba32F => 16,
+        }
+    }
+
+    /// Bytes per pixel of the data from [`TextureFormat::pixel_data`].
+    pub fn pixel_size(self) -> usize {
+        if self.is_float() {
+            self.channels() * 4
+        } else if self.is_16_bit() {
+            self.channels() * 2
+        } else {
+            self.channels()
+        }
+    }
+
+    /// Converts `image` into tightly packed rows for this format.
+    ///
+    /// Float formats hold linear values, so an sRGB encoded source is decoded on the way.
+    pub fn pixel_data(self, image: &DynamicImage, color_space: ColorSpace) -> Vec<u8> {
+        if self.is_16_bit() {
+            let values = match self.channels() {
+                1 => image.to_luma16().into_raw(),
+                2 => image.to_luma_alpha16().into_raw(),
+                3 => image.to_rgb16().into_raw(),
+                _ => image.to_rgba16().into_raw(),
+            };
+            return values
+                .iter()
+                .flat_map(|value| value.to_ne_bytes())
+                .collect();
+        }
+
+        if !self.is_float() {
+            return match self.channels() {
+                1 => image.to_luma8().into_raw(),
+                2 => image.to_luma_alpha8().into_raw(),
+                3 => image.to_rgb8().into_raw(),
+                _ => image.to_rgba8().into_raw(),
+            };
+        }
+
+        let decode = |value: f32| match color_space {
+            ColorSpace::Linear => value,
+            ColorSpace::Srgb => srgb_to_linear(value),
+        };
+
+        let rgba = image.to_rgba32f();
+        let mut data = Vec::with_capacity(rgba.len() / 4 * self.pixel_size());
+        for pixel in rgba.pixels() {
+            let [r, g, b, a] = pixel.0;
+            let (r, g, b) = (decode(r), decode(g), decode(b));
+            let luma = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+
+            let channels: &[f32] = match self.channels() {
+                1 => &[luma],
+                2 => &[luma, a],
+                3 => &[r, g, b],
+                _ => &[r, g, b, a],
+            };
+            data.extend(channels.iter().flat_map(|channel| channel.to_ne_bytes()));
+        }
+
+        data
+    }
+}
+
+/// Runs `f` with `PACK_ALIGNMENT` and `UNPACK_ALIGNMENT` at 1 when rows of `format` data
+/// aren't 4 byte aligned, as with R, RG and RGB data, then puts back the default of 4.
+// `usize::is_multiple_of` needs Rust 1.87, older clippies don't know the lint suggesting it
+#[allow(unknown_lints, clippy::manual_is_multiple_of)]
+pub(crate) unsafe fn with_row_alignment<T>(
+    gl: &glow::Context,
+    format: TextureFormat,
+    f: impl FnOnce() -> T,
+) -> T {
+    if format.pixel_size() % 4 == 0 {
+        return f();
+    }
+
+    gl.pixel_store_i32(glow::PACK_ALIGNMENT, 1);
+    gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+    let result = f();
+    gl.pixel_store_i32(glow::PACK_ALIGNMENT, 4);
+    gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 4);
+    result
+}
+
+pub(crate) fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
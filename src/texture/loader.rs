@@ -0,0 +1,239 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+use super::{CreationError, DecodedImage, PixelFormat, SamplerDesc, Texture, TextureOptions};
+
+/// Loads textures in the background: files are read and decoded on worker threads and uploaded
+/// on the context thread by [`TextureLoader::poll`].
+///
+/// Until its upload, a [`TextureHandle`] hands out a placeholder texture.
+pub struct TextureLoader {
+    gl: Rc<glow::Context>,
+    placeholder: Rc<Texture>,
+    jobs: Option<mpsc::Sender<Job>>,
+    results: mpsc::Receiver<JobResult>,
+    workers: Vec<thread::JoinHandle<()>>,
+    pending: HashMap<u64, TextureHandle>,
+    next_id: u64,
+}
+
+/// Where a texture started with [`TextureLoader::load`] is at.
+#[derive(Debug, Clone)]
+pub enum LoadStatus {
+    Loading,
+    Ready,
+    Failed(Rc<CreationError>),
+}
+
+/// A texture that may still be loading, cheap to clone.
+#[derive(Clone)]
+pub struct TextureHandle {
+    path: Rc<Path>,
+    placeholder: Rc<Texture>,
+    state: Rc<RefCell<LoadState>>,
+}
+
+enum LoadState {
+    Loading,
+    Ready(Rc<Texture>),
+    Failed(Rc<CreationError>),
+}
+
+struct Job {
+    id: u64,
+    path: PathBuf,
+    options: TextureOptions,
+}
+
+struct JobResult {
+    id: u64,
+    path: PathBuf,
+    /// The decoded file and the options its sidecar left
+    decoded: Result<(DecodedImage, TextureOptions), CreationError>,
+}
+
+impl TextureLoader {
+    /// Starts a loader with one worker per core, up to four.
+    pub fn new(gl: Rc<glow::Context>) -> Result<Self, CreationError> {
+        let workers = thread::available_parallelism().map_or(1, |count| count.get().min(4));
+        Self::with_workers(gl, workers)
+    }
+
+    /// Starts a loader with `workers` decoding threads, at least one.
+    ///
+    /// The default placeholder is a single mid gray texel.
+    pub fn with_workers(gl: Rc<glow::Context>, workers: usize) -> Result<Self, CreationError> {
+        let placeholder = Texture::from_pixels(
+            Rc::clone(&gl),
+            1,
+            1,
+            PixelFormat::Rgba8,
+            &[128, 128, 128, 255],
+            &TextureOptions {
+                sampler: SamplerDesc::nearest(),
+                ..Default::default()
+            },
+        )?;
+
+        let (job_sender, job_receiver) = mpsc::channel::<Job>();
+        let (result_sender, results) = mpsc::channel();
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+
+        let workers = (0..workers.max(1))
+            .map(|index| {
+                let jobs = Arc::clone(&job_receiver);
+                let results = result_sender.clone();
+
+                thread::Builder::new()
+                    .name(format!("texture-loader-{index}"))
+                    .spawn(move || loop {
+                        // The lock is released before decoding so other workers can take jobs
+                        let job = match jobs.lock() {
+                            Ok(jobs) => jobs.recv(),
+                            Err(_) => return,
+                        };
+                        let Ok(job) = job else { return };
+
+                        let decoded = Texture::decode(&job.path, &job.options);
+                        let result = JobResult {
+                            id: job.id,
+                            path: job.path,
+                            decoded,
+                        };
+                        if results.send(result).is_err() {
+                            return;
+                        }
+                    })
+                    .expect("Failed to spawn texture loader thread")
+            })
+            .collect();
+
+        Ok(TextureLoader {
+            gl,
+            placeholder: Rc::new(placeholder),
+            jobs: Some(job_sender),
+            results,
+            workers,
+            pending: HashMap::new(),
+            next_id: 0,
+        })
+    }
+
+    /// Replaces the placeholder of handles created after this call.
+    pub fn set_placeholder(&mut self, placeholder: Rc<Texture>) {
+        self.placeholder = placeholder;
+    }
+
+    /// Queues `path` for decoding, see [`Texture::with_options`] for the supported files.
+    pub fn load(&mut self, path: &Path, options: &TextureOptions) -> TextureHandle {
+        let handle = TextureHandle {
+            path: Rc::from(path),
+            placeholder: Rc::clone(&self.placeholder),
+            state: Rc::new(RefCell::new(LoadState::Loading)),
+        };
+
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let job = Job {
+            id,
+            path: PathBuf::from(path),
+            options: options.clone(),
+        };
+        let sent = self
+            .jobs
+            .as_ref()
+            .is_some_and(|jobs| jobs.send(job).is_ok());
+
+        if sent {
+            self.pending.insert(id, handle.clone());
+        } else {
+            // Only happens if every worker has died, decode here instead of never finishing
+            let decoded = Texture::decode(path, options);
+            handle.finish(decoded.and_then(|(decoded, options)| {
+                Texture::from_decoded(Rc::clone(&self.gl), decoded, path, &options)
+            }));
+        }
+
+        handle
+    }
+
+    /// Uploads every texture that finished decoding since the last call, call it once per frame.
+    ///
+    /// Returns the handles that became ready or failed, so their textures can be rebound.
+    pub fn poll(&mut self) -> Vec<TextureHandle> {
+        let mut finished = Vec::new();
+
+        while let Ok(result) = self.results.try_recv() {
+            let Some(handle) = self.pending.remove(&result.id) else {
+                continue;
+            };
+
+            handle.finish(result.decoded.and_then(|(decoded, options)| {
+                Texture::from_decoded(Rc::clone(&self.gl), decoded, &result.path, &options)
+            }));
+            finished.push(handle);
+        }
+
+        finished
+    }
+
+    /// Number of textures still being decoded or waiting for [`TextureLoader::poll`].
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+impl Drop for TextureLoader {
+    fn drop(&mut self) {
+        // Closing the channel ends the workers once they finish their current file
+        self.jobs = None;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl TextureHandle {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn status(&self) -> LoadStatus {
+        match &*self.state.borrow() {
+            LoadState::Loading => LoadStatus::Loading,
+            LoadState::Ready(_) => LoadStatus::Ready,
+            LoadState::Failed(error) => LoadStatus::Failed(Rc::clone(error)),
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        matches!(*self.state.borrow(), LoadState::Ready(_))
+    }
+
+    /// The loaded texture, or the placeholder while loading and after a failure.
+    pub fn texture(&self) -> Rc<Texture> {
+        match &*self.state.borrow() {
+            LoadState::Ready(texture) => Rc::clone(texture),
+            _ => Rc::clone(&self.placeholder),
+        }
+    }
+
+    /// Binds [`TextureHandle::texture`] to `TEXTURE_2D` of the active unit.
+    pub fn bind(&self) {
+        self.texture().bind()
+    }
+
+    fn finish(&self, result: Result<Texture, CreationError>) {
+        *self.state.borrow_mut() = match result {
+            Ok(texture) => LoadState::Ready(Rc::new(texture)),
+            Err(error) => LoadState::Failed(Rc::new(error)),
+        };
+    }
+}
@@ -0,0 +1,97 @@
+use std::{
+    ffi::OsString,
+    io,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use super::{ColorSpace, CreationError, Filter, Mipmaps, TextureOptions, WrapMode};
+
+/// Import settings kept next to an image in a TOML sidecar named after it with `.meta` added,
+/// `bricks.png.meta` for `bricks.png`.
+///
+/// Every setting is optional and overrides the [`TextureOptions`] passed in code:
+///
+/// ```toml
+/// color_space = "srgb"
+/// wrap = "clamp_to_edge"
+/// filter = "nearest"
+/// mipmaps = { cpu = "kaiser" }
+/// flip_vertically = false
+/// compressed = "bricks.ktx2"
+/// ```
+///
+/// [`super::Texture::with_options`], and everything built on it, picks the sidecar up.
+/// Arrays, cubemaps and atlases share one set of options between several files and ignore it.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TextureMeta {
+    pub color_space: Option<ColorSpace>,
+    /// Wrap mode on every axis
+    pub wrap: Option<WrapMode>,
+    /// Minification, magnification and, when mipmapped, mip filter
+    pub filter: Option<Filter>,
+    pub mipmaps: Option<Mipmaps>,
+    pub flip_vertically: Option<bool>,
+    /// A KTX2 or DDS container with a block compressed version of the image, relative to the
+    /// sidecar, loaded in place of the image
+    pub compressed: Option<PathBuf>,
+}
+
+impl TextureMeta {
+    /// Where the sidecar of `image_path` would be.
+    pub fn path_for(image_path: &Path) -> PathBuf {
+        let mut path = OsString::from(image_path);
+        path.push(".meta");
+        PathBuf::from(path)
+    }
+
+    /// Reads the sidecar of `image_path`, `None` if there is none.
+    pub fn load(image_path: &Path) -> Result<Option<Self>, CreationError> {
+        let path = Self::path_for(image_path);
+        let source = match std::fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(CreationError::FileOpenFailed {
+                    error_message: err.to_string(),
+                    file_path: path,
+                    io_error: err,
+                })
+            }
+        };
+
+        let mut meta: TextureMeta = toml::from_str(&source)
+            .map_err(|error| CreationError::MetaParsingFailed { path, error })?;
+
+        let directory = image_path.parent().unwrap_or_else(|| Path::new(""));
+        meta.compressed = meta.compressed.map(|compressed| directory.join(compressed));
+        Ok(Some(meta))
+    }
+
+    /// `options` with the settings of this sidecar on top.
+    pub fn apply(&self, options: &TextureOptions) -> TextureOptions {
+        let mut options = options.clone();
+
+        if let Some(color_space) = self.color_space {
+            options.color_space = color_space;
+        }
+        if let Some(wrap) = self.wrap {
+            options.sampler = options.sampler.with_wrap(wrap);
+        }
+        if let Some(filter) = self.filter {
+            options.sampler.min_filter = filter;
+            options.sampler.mag_filter = filter;
+            options.sampler.mipmap_filter = options.sampler.mipmap_filter.map(|_| filter);
+        }
+        if let Some(mipmaps) = self.mipmaps {
+            options.mipmaps = mipmaps;
+        }
+        if let Some(flip_vertically) = self.flip_vertically {
+            options.flip_vertically = flip_vertically;
+        }
+
+        options
+    }
+}
@@ -0,0 +1,204 @@
+use std::f32::consts::PI;
+
+use image::{ColorType, DynamicImage, Rgba32FImage};
+use serde::Deserialize;
+
+use super::{format::srgb_to_linear, ColorSpace};
+
+/// How the mip levels of a texture are made.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mipmaps {
+    /// Only the full size level, sampled without mipmapping
+    None,
+    /// `generate_mipmap`, a box filter on most drivers
+    #[default]
+    Driver,
+    /// [`generate_mipmaps`] on the CPU before upload
+    Cpu(MipFilter),
+}
+
+/// Downsampling filters for [`generate_mipmaps`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MipFilter {
+    /// Windowed sinc with three lobes, sharp with slight ringing
+    Lanczos3,
+    /// Kaiser windowed sinc, a little softer than Lanczos with less ringing
+    Kaiser,
+}
+
+impl MipFilter {
+    /// Filter support in destination pixels.
+    fn radius(self) -> f32 {
+        3.0
+    }
+
+    fn weight(self, x: f32) -> f32 {
+        let x = x.abs();
+        if x >= self.radius() {
+            return 0.0;
+        }
+
+        match self {
+            MipFilter::Lanczos3 => sinc(x) * sinc(x / 3.0),
+            MipFilter::Kaiser => {
+                const ALPHA: f32 = 4.0;
+                let t = x / self.radius();
+                sinc(x) * bessel_i0(ALPHA * (1.0 - t * t).sqrt()) / bessel_i0(ALPHA)
+            }
+        }
+    }
+}
+
+/// Every mip level below `image`, down to 1x1, each half the size of the previous one rounded
+/// down.
+///
+/// Levels keep the color type of `image`. With [`ColorSpace::Srgb`] the color channels are
+/// decoded to linear before filtering and encoded again afterwards, so averages don't darken.
+pub fn generate_mipmaps(
+    image: &DynamicImage,
+    filter: MipFilter,
+    color_space: ColorSpace,
+) -> Vec<DynamicImage> {
+    let mut level = image.to_rgba32f();
+    if color_space == ColorSpace::Srgb {
+        map_color(&mut level, srgb_to_linear);
+    }
+
+    let mut levels = Vec::new();
+    while level.width() > 1 || level.height() > 1 {
+        let width = (level.width() / 2).max(1);
+        let height = (level.height() / 2).max(1);
+        level = resample_rows(&level, width, filter);
+        level = resample_columns(&level, height, filter);
+
+        let mut encoded = level.clone();
+        if color_space == ColorSpace::Srgb {
+            map_color(&mut encoded, linear_to_srgb);
+        }
+        levels.push(convert(encoded, image.color()));
+    }
+
+    levels
+}
+
+/// Shrinks every row to `width` pixels.
+fn resample_rows(image: &Rgba32FImage, width: u32, filter: MipFilter) -> Rgba32FImage {
+    let weights = weights(image.width(), width, filter);
+    Rgba32FImage::from_fn(width, image.height(), |x, y| {
+        let mut sum = [0.0; 4];
+        for &(source, weight) in &weights[x as usize] {
+            let pixel = image.get_pixel(source, y).0;
+            for i in 0..4 {
+                sum[i] += pixel[i] * weight;
+            }
+        }
+        image::Rgba(clamp(sum))
+    })
+}
+
+/// Shrinks every column to `height` pixels.
+fn resample_columns(image: &Rgba32FImage, height: u32, filter: MipFilter) -> Rgba32FImage {
+    let weights = weights(image.height(), height, filter);
+    Rgba32FImage::from_fn(image.width(), height, |x, y| {
+        let mut sum = [0.0; 4];
+        for &(source, weight) in &weights[y as usize] {
+            let pixel = image.get_pixel(x, source).0;
+            for i in 0..4 {
+                sum[i] += pixel[i] * weight;
+            }
+        }
+        image::Rgba(clamp(sum))
+    })
+}
+
+/// Normalized source pixel weights for every destination pixel, clamping at the edges.
+fn weights(source_size: u32, destination_size: u32, filter: MipFilter) -> Vec<Vec<(u32, f32)>> {
+    let scale = source_size as f32 / destination_size as f32;
+    let support = filter.radius() * scale;
+
+    (0..destination_size)
+        .map(|x| {
+            let center = (x as f32 + 0.5) * scale;
+            let first = (center - support).floor() as i64;
+            let last = (center + support).ceil() as i64;
+
+            let mut taps: Vec<(u32, f32)> = (first..=last)
+                .map(|i| {
+                    let weight = filter.weight((i as f32 + 0.5 - center) / scale);
+                    (i.clamp(0, source_size as i64 - 1) as u32, weight)
+                })
+                .filter(|&(_, weight)| weight != 0.0)
+                .collect();
+
+            let total: f32 = taps.iter().map(|&(_, weight)| weight).sum();
+            for (_, weight) in &mut taps {
+                *weight /= total;
+            }
+            taps
+        })
+        .collect()
+}
+
+/// Removes the overshoot of negative lobes.
+fn clamp([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    [r.max(0.0), g.max(0.0), b.max(0.0), a.clamp(0.0, 1.0)]
+}
+
+fn map_color(image: &mut Rgba32FImage, f: fn(f32) -> f32) {
+    for pixel in image.pixels_mut() {
+        for channel in &mut pixel.0[..3] {
+            *channel = f(*channel);
+        }
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Converts a filtered level back to the color type of the source image.
+fn convert(level: Rgba32FImage, color: ColorType) -> DynamicImage {
+    let level = DynamicImage::ImageRgba32F(level);
+    match color {
+        ColorType::L8 => DynamicImage::ImageLuma8(level.to_luma8()),
+        ColorType::La8 => DynamicImage::ImageLumaA8(level.to_luma_alpha8()),
+        ColorType::Rgb8 => DynamicImage::ImageRgb8(level.to_rgb8()),
+        ColorType::Rgba8 => DynamicImage::ImageRgba8(level.to_rgba8()),
+        ColorType::L16 => DynamicImage::ImageLuma16(level.to_luma16()),
+        ColorType::La16 => DynamicImage::ImageLumaA16(level.to_luma_alpha16()),
+        ColorType::Rgb16 => DynamicImage::ImageRgb16(level.to_rgb16()),
+        ColorType::Rgba16 => DynamicImage::ImageRgba16(level.to_rgba16()),
+        ColorType::Rgb32F => DynamicImage::ImageRgb32F(level.to_rgb32f()),
+        _ => level,
+    }
+}
+
+fn sinc(x: f32) -> f32 {
+    if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Modified Bessel function of the first kind, order zero, by its power series.
+fn bessel_i0(x: f32) -> f32 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half_squared = x * x / 4.0;
+
+    for k in 1..20 {
+        term *= half_squared / (k * k) as f32;
+        sum += term;
+        if term < sum * 1e-7 {
+            break;
+        }
+    }
+    sum
+}
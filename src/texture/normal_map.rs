@@ -0,0 +1,91 @@
+use std::rc::Rc;
+
+use image::{DynamicImage, RgbImage};
+
+use super::{ColorSpace, CreationError, Texture, TextureOptions};
+
+/// Derivative kernels for [`normal_map_from_height`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalFilter {
+    /// 3x3 Sobel, the usual choice
+    Sobel,
+    /// 3x3 Scharr, closer to rotationally symmetric so diagonal slopes keep their strength
+    Scharr,
+}
+
+impl NormalFilter {
+    /// Weights of the outer and middle rows, scaled so a slope of one per pixel gives one.
+    fn weights(self) -> (f32, f32) {
+        match self {
+            NormalFilter::Sobel => (1.0 / 8.0, 2.0 / 8.0),
+            NormalFilter::Scharr => (3.0 / 32.0, 10.0 / 32.0),
+        }
+    }
+}
+
+/// Turns a height map into a tangent space normal map.
+///
+/// Heights are the luminance of `height` from 0 to 1, and `strength` scales the slopes, in
+/// heights per pixel, before they become normals. Edges are clamped.
+///
+/// Like a decoded file the image is expected top row first. The result uses the OpenGL
+/// convention with green pointing up the image and is in the same orientation, encoded as
+/// `normal * 0.5 + 0.5` in an `Rgb8` image to be sampled as linear data.
+pub fn normal_map_from_height(
+    height: &DynamicImage,
+    filter: NormalFilter,
+    strength: f32,
+) -> DynamicImage {
+    let heights = height.to_luma32f();
+    let (width, rows) = heights.dimensions();
+    let (outer, middle) = filter.weights();
+
+    let at = |x: i64, y: i64| {
+        let x = x.clamp(0, width as i64 - 1) as u32;
+        let y = y.clamp(0, rows as i64 - 1) as u32;
+        heights.get_pixel(x, y).0[0]
+    };
+
+    DynamicImage::ImageRgb8(RgbImage::from_fn(width, rows, |x, y| {
+        let (x, y) = (x as i64, y as i64);
+        let dx = outer * (at(x + 1, y - 1) - at(x - 1, y - 1))
+            + middle * (at(x + 1, y) - at(x - 1, y))
+            + outer * (at(x + 1, y + 1) - at(x - 1, y + 1));
+        let dy = outer * (at(x - 1, y + 1) - at(x - 1, y - 1))
+            + middle * (at(x, y + 1) - at(x, y - 1))
+            + outer * (at(x + 1, y + 1) - at(x + 1, y - 1));
+
+        // Rows count down the image, so rising towards later rows tilts the normal up
+        let normal = glam::vec3(-dx * strength, dy * strength, 1.0).normalize();
+        let encode = |value: f32| ((value * 0.5 + 0.5) * 255.0).round() as u8;
+        image::Rgb([encode(normal.x), encode(normal.y), encode(normal.z)])
+    }))
+}
+
+impl Texture {
+    /// Creates a normal map texture from a height map, see [`normal_map_from_height`].
+    ///
+    /// `height` is flipped like a decoded file when [`TextureOptions::flip_vertically`] is set.
+    /// Normals are data, so [`TextureOptions::color_space`] is ignored and always linear.
+    pub fn from_height_map(
+        gl: Rc<glow::Context>,
+        height: &DynamicImage,
+        filter: NormalFilter,
+        strength: f32,
+        options: &TextureOptions,
+    ) -> Result<Self, CreationError> {
+        let options = TextureOptions {
+            color_space: ColorSpace::Linear,
+            ..options.clone()
+        };
+        let normals = options.orient(normal_map_from_height(height, filter, strength));
+
+        Self::upload(gl, &normals, &options, options.flip_vertically).map_err(|error_message| {
+            CreationError::TextureCreationFailed {
+                path: None,
+                extension: None,
+                error_message,
+            }
+        })
+    }
+}
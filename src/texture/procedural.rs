@@ -0,0 +1,244 @@
+use std::rc::Rc;
+
+use image::{DynamicImage, GrayImage, Rgba, RgbaImage};
+
+use super::{CreationError, Mipmaps, SamplerDesc, Texture, TextureOptions};
+
+/// Noise functions for [`noise`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Noise {
+    /// Gradient noise on a square lattice
+    Perlin,
+    /// Gradient noise on a triangular lattice, with fewer axis aligned artifacts than Perlin
+    Simplex,
+    /// Distance to the nearest of one random point per cell, a cellular pattern
+    Worley,
+}
+
+/// Direction of a [`gradient`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GradientDirection {
+    /// From the left column to the right one
+    Horizontal,
+    /// From the first row to the last one
+    Vertical,
+}
+
+/// An image of a single color.
+pub fn solid(width: u32, height: u32, color: Rgba<u8>) -> DynamicImage {
+    DynamicImage::ImageRgba8(RgbaImage::from_pixel(width, height, color))
+}
+
+/// Squares of `cell_size` pixels alternating between `first`, in the corner at the first
+/// pixel, and `second`.
+pub fn checkerboard(
+    width: u32,
+    height: u32,
+    cell_size: u32,
+    first: Rgba<u8>,
+    second: Rgba<u8>,
+) -> DynamicImage {
+    let cell_size = cell_size.max(1);
+    DynamicImage::ImageRgba8(RgbaImage::from_fn(width, height, |x, y| {
+        if (x / cell_size + y / cell_size) & 1 == 0 {
+            first
+        } else {
+            second
+        }
+    }))
+}
+
+/// A linear blend from `from` at the first column or row to `to` at the last one.
+pub fn gradient(
+    width: u32,
+    height: u32,
+    from: Rgba<u8>,
+    to: Rgba<u8>,
+    direction: GradientDirection,
+) -> DynamicImage {
+    let steps = match direction {
+        GradientDirection::Horizontal => width,
+        GradientDirection::Vertical => height,
+    }
+    .saturating_sub(1)
+    .max(1) as f32;
+
+    DynamicImage::ImageRgba8(RgbaImage::from_fn(width, height, |x, y| {
+        let t = match direction {
+            GradientDirection::Horizontal => x,
+            GradientDirection::Vertical => y,
+        } as f32
+            / steps;
+        Rgba(std::array::from_fn(|i| {
+            (from[i] as f32 + (to[i] as f32 - from[i] as f32) * t).round() as u8
+        }))
+    }))
+}
+
+/// Grayscale noise with features about `cell_size` pixels apart.
+///
+/// The same `seed` always gives the same image. Perlin and simplex noise are centered on mid
+/// gray, Worley noise is black at the feature points and brightens away from them.
+pub fn noise(width: u32, height: u32, kind: Noise, cell_size: f32, seed: u32) -> DynamicImage {
+    let cell_size = cell_size.max(f32::EPSILON);
+    DynamicImage::ImageLuma8(GrayImage::from_fn(width, height, |x, y| {
+        let x = (x as f32 + 0.5) / cell_size;
+        let y = (y as f32 + 0.5) / cell_size;
+        let value = match kind {
+            Noise::Perlin => perlin(x, y, seed) * 0.5 + 0.5,
+            Noise::Simplex => simplex(x, y, seed) * 0.5 + 0.5,
+            Noise::Worley => worley(x, y, seed),
+        };
+        image::Luma([(value.clamp(0.0, 1.0) * 255.0).round() as u8])
+    }))
+}
+
+/// A UV debug image: red follows `s`, green follows `t` and white lines split it into
+/// `cells` by `cells` squares.
+///
+/// Uploaded with [`Texture::from_image`], which does not flip, the first row is `t = 0`, so
+/// the colors show the texture coordinates a mesh samples with.
+pub fn uv_grid(width: u32, height: u32, cells: u32) -> DynamicImage {
+    let cells = cells.max(1);
+    let is_line = |position: u32, size: u32| {
+        let cell = position * cells / size.max(1);
+        position == 0 || position == size - 1 || cell != (position - 1) * cells / size
+    };
+
+    DynamicImage::ImageRgba8(RgbaImage::from_fn(width, height, |x, y| {
+        if is_line(x, width) || is_line(y, height) {
+            return Rgba([255, 255, 255, 255]);
+        }
+        let s = (x as f32 + 0.5) / width as f32;
+        let t = (y as f32 + 0.5) / height as f32;
+        Rgba([(s * 255.0).round() as u8, (t * 255.0).round() as u8, 0, 255])
+    }))
+}
+
+/// The magenta and black checkerboard used for textures that failed to load.
+pub fn missing_image(size: u32) -> DynamicImage {
+    checkerboard(
+        size,
+        size,
+        (size / 8).max(1),
+        Rgba([255, 0, 255, 255]),
+        Rgba([0, 0, 0, 255]),
+    )
+}
+
+impl Texture {
+    /// A small magenta and black checkerboard that is hard to miss, to stand in for a texture
+    /// that failed to load, as in `Texture::new(gl, path).or_else(|_| Texture::missing(gl))`.
+    pub fn missing(gl: Rc<glow::Context>) -> Result<Self, CreationError> {
+        let options = TextureOptions {
+            sampler: SamplerDesc::nearest(),
+            mipmaps: Mipmaps::None,
+            ..Default::default()
+        };
+        Self::from_image(gl, &missing_image(64), &options)
+    }
+}
+
+/// Classic Perlin noise, roughly in -1 to 1.
+fn perlin(x: f32, y: f32, seed: u32) -> f32 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let (ix, iy) = (x0 as i32, y0 as i32);
+
+    let corner = |dx: i32, dy: i32| {
+        let [gx, gy] = gradient_at(hash(ix + dx, iy + dy, seed));
+        gx * (fx - dx as f32) + gy * (fy - dy as f32)
+    };
+
+    let (u, v) = (fade(fx), fade(fy));
+    let bottom = lerp(corner(0, 0), corner(1, 0), u);
+    let top = lerp(corner(0, 1), corner(1, 1), u);
+
+    // Unit gradients peak at 1/sqrt(2)
+    lerp(bottom, top, v) * std::f32::consts::SQRT_2
+}
+
+/// 2D simplex noise, roughly in -1 to 1.
+fn simplex(x: f32, y: f32, seed: u32) -> f32 {
+    // Skews the triangular lattice onto a square one and back
+    const F2: f32 = 0.366_025_4; // (sqrt(3) - 1) / 2
+    const G2: f32 = 0.211_324_87; // (3 - sqrt(3)) / 6
+
+    let skew = (x + y) * F2;
+    let (i, j) = ((x + skew).floor(), (y + skew).floor());
+    let unskew = (i + j) * G2;
+    let (x0, y0) = (x - (i - unskew), y - (j - unskew));
+
+    // The lower or upper triangle of the skewed cell
+    let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+    let corners = [
+        (0, 0, x0, y0),
+        (i1, j1, x0 - i1 as f32 + G2, y0 - j1 as f32 + G2),
+        (1, 1, x0 - 1.0 + 2.0 * G2, y0 - 1.0 + 2.0 * G2),
+    ];
+
+    let (i, j) = (i as i32, j as i32);
+    let sum: f32 = corners
+        .iter()
+        .map(|&(di, dj, dx, dy)| {
+            let falloff = 0.5 - dx * dx - dy * dy;
+            if falloff <= 0.0 {
+                return 0.0;
+            }
+            let [gx, gy] = gradient_at(hash(i + di, j + dj, seed));
+            falloff.powi(4) * (gx * dx + gy * dy)
+        })
+        .sum();
+
+    // Scales the sum to about -1 to 1
+    70.0 * sum
+}
+
+/// Distance to the nearest feature point in cells, clamped to 0 to 1.
+fn worley(x: f32, y: f32, seed: u32) -> f32 {
+    let (ix, iy) = (x.floor() as i32, y.floor() as i32);
+
+    let mut nearest = f32::MAX;
+    for cy in iy - 1..=iy + 1 {
+        for cx in ix - 1..=ix + 1 {
+            let h = hash(cx, cy, seed);
+            let px = cx as f32 + (h & 0xffff) as f32 / 65536.0;
+            let py = cy as f32 + (h >> 16) as f32 / 65536.0;
+            nearest = nearest.min((px - x).hypot(py - y));
+        }
+    }
+    nearest.min(1.0)
+}
+
+/// One of eight unit gradient directions.
+fn gradient_at(hash: u32) -> [f32; 2] {
+    const DIAGONAL: f32 = std::f32::consts::FRAC_1_SQRT_2;
+    match hash & 7 {
+        0 => [1.0, 0.0],
+        1 => [-1.0, 0.0],
+        2 => [0.0, 1.0],
+        3 => [0.0, -1.0],
+        4 => [DIAGONAL, DIAGONAL],
+        5 => [-DIAGONAL, DIAGONAL],
+        6 => [DIAGONAL, -DIAGONAL],
+        _ => [-DIAGONAL, -DIAGONAL],
+    }
+}
+
+/// Mixes lattice coordinates and a seed into well spread bits.
+fn hash(x: i32, y: i32, seed: u32) -> u32 {
+    let mut h = seed ^ (x as u32).wrapping_mul(0x27d4_eb2d) ^ (y as u32).wrapping_mul(0x1656_67b1);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x2c1b_3c6d);
+    h ^= h >> 12;
+    h = h.wrapping_mul(0x297a_2d39);
+    h ^ (h >> 15)
+}
+
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
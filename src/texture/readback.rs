@@ -0,0 +1,181 @@
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use glow::HasContext;
+use image::{DynamicImage, ImageFormat, Rgb32FImage, Rgba32FImage};
+use thiserror::Error;
+
+use super::{format::with_row_alignment, Texture};
+
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("No file extension")]
+    NoFileExtension { path: PathBuf },
+
+    #[error("Unknown file extension: {extension:?}")]
+    UnknownFileExtension { path: PathBuf, extension: OsString },
+
+    #[error("Failed to save image: {error}")]
+    ImageSavingFailed {
+        path: PathBuf,
+        error: image::ImageError,
+    },
+}
+
+impl Texture {
+    /// Reads the full size level back from the GPU.
+    ///
+    /// Flipped textures are flipped back, so a texture loaded from a file reads back the way the
+    /// file looks, whatever [`super::TextureOptions::flip_vertically`] was set to.
+    ///
+    /// 8 and 16-bit formats give 8 and 16-bit images, float formats give `Rgb32F` or `Rgba32F`
+    /// with single channel values spread over RGB. sRGB textures return the encoded values and compressed
+    /// ones are decompressed by the driver.
+    ///
+    /// This binds the texture to the active texture unit.
+    pub fn read_pixels(&self) -> DynamicImage {
+        let (width, height) = (self.width, self.height);
+        let format = self.format;
+
+        // One and two channel floats are read as RGBA, there is no float luma image type
+        let channels = if format.is_float() {
+            4
+        } else {
+            format.channels()
+        };
+        let pixel_format = match channels {
+            1 => glow::RED,
+            2 => glow::RG,
+            3 => glow::RGB,
+            _ => glow::RGBA,
+        };
+        let texel_size = channels
+            * if format.is_float() {
+                4
+            } else if format.is_16_bit() {
+                2
+            } else {
+                1
+            };
+
+        let mut data = vec![0u8; width as usize * height as usize * texel_size];
+        unsafe {
+            self.bind();
+
+            with_row_alignment(&self.gl, format, || {
+                self.gl.get_tex_image(
+                    glow::TEXTURE_2D,
+                    0,
+                    pixel_format,
+                    format.pixel_type(),
+                    glow::PixelPackData::Slice(&mut data),
+                )
+            });
+        }
+
+        let image = if format.is_float() {
+            let values: Vec<f32> = data
+                .chunks_exact(4)
+                .map(|bytes| f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect();
+
+            let texel = |x: u32, y: u32| {
+                let i = (y * width + x) as usize * 4;
+                [values[i], values[i + 1], values[i + 2], values[i + 3]]
+            };
+
+            match format.channels() {
+                1 => DynamicImage::ImageRgb32F(Rgb32FImage::from_fn(width, height, |x, y| {
+                    let [r, ..] = texel(x, y);
+                    image::Rgb([r, r, r])
+                })),
+                2 => DynamicImage::ImageRgba32F(Rgba32FImage::from_fn(width, height, |x, y| {
+                    let [r, a, ..] = texel(x, y);
+                    image::Rgba([r, r, r, a])
+                })),
+                3 => DynamicImage::ImageRgb32F(Rgb32FImage::from_fn(width, height, |x, y| {
+                    let [r, g, b, _] = texel(x, y);
+                    image::Rgb([r, g, b])
+                })),
+                _ => DynamicImage::ImageRgba32F(Rgba32FImage::from_fn(width, height, |x, y| {
+                    image::Rgba(texel(x, y))
+                })),
+            }
+        } else if format.is_16_bit() {
+            let values: Vec<u16> = data
+                .chunks_exact(2)
+                .map(|bytes| u16::from_ne_bytes([bytes[0], bytes[1]]))
+                .collect();
+
+            match channels {
+                1 => image::ImageBuffer::from_raw(width, height, values)
+                    .map(DynamicImage::ImageLuma16),
+                2 => image::ImageBuffer::from_raw(width, height, values)
+                    .map(DynamicImage::ImageLumaA16),
+                3 => image::ImageBuffer::from_raw(width, height, values)
+                    .map(DynamicImage::ImageRgb16),
+                _ => image::ImageBuffer::from_raw(width, height, values)
+                    .map(DynamicImage::ImageRgba16),
+            }
+            .expect("Read back buffer matches the texture size")
+        } else {
+            match channels {
+                1 => image::GrayImage::from_raw(width, height, data).map(DynamicImage::ImageLuma8),
+                2 => image::GrayAlphaImage::from_raw(width, height, data)
+                    .map(DynamicImage::ImageLumaA8),
+                3 => image::RgbImage::from_raw(width, height, data).map(DynamicImage::ImageRgb8),
+                _ => image::RgbaImage::from_raw(width, height, data).map(DynamicImage::ImageRgba8),
+            }
+            .expect("Read back buffer matches the texture size")
+        };
+
+        if self.flipped {
+            image.flipv()
+        } else {
+            image
+        }
+    }
+
+    /// Reads the texture back and writes it to `path`, the format is picked from the extension.
+    ///
+    /// Float textures are kept as floats in `.hdr` and `.exr` files and clamped to 8 bits
+    /// otherwise.
+    pub fn save(&self, path: &Path) -> Result<(), ExportError> {
+        let format = match path.extension() {
+            Some(ext) => match ImageFormat::from_extension(ext) {
+                Some(format) => format,
+                None => {
+                    return Err(ExportError::UnknownFileExtension {
+                        path: PathBuf::from(path),
+                        extension: ext.to_os_string(),
+                    })
+                }
+            },
+            None => {
+                return Err(ExportError::NoFileExtension {
+                    path: PathBuf::from(path),
+                })
+            }
+        };
+
+        let keeps_floats = matches!(format, ImageFormat::Hdr | ImageFormat::OpenExr);
+        let image = match self.read_pixels() {
+            image @ DynamicImage::ImageRgb32F(_) if !keeps_floats => {
+                DynamicImage::ImageRgb8(image.to_rgb8())
+            }
+            image @ DynamicImage::ImageRgba32F(_) if !keeps_floats => {
+                DynamicImage::ImageRgba8(image.to_rgba8())
+            }
+            image => image,
+        };
+
+        image
+            .save_with_format(path, format)
+            .map_err(|error| ExportError::ImageSavingFailed {
+                path: PathBuf::from(path),
+                error,
+            })
+    }
+}
@@ -0,0 +1,192 @@
+use std::rc::Rc;
+
+use glow::HasContext;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WrapMode {
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+    ClampToBorder,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Filter {
+    Nearest,
+    Linear,
+}
+
+/// How a texture is sampled.
+///
+/// The default is repeating, trilinear filtering without anisotropy.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SamplerDesc {
+    pub wrap_s: WrapMode,
+    pub wrap_t: WrapMode,
+    pub wrap_r: WrapMode,
+    pub min_filter: Filter,
+    pub mag_filter: Filter,
+    /// Filter between mip levels, `None` samples the base level only
+    pub mipmap_filter: Option<Filter>,
+    /// Clamped to the driver maximum, ignored without anisotropic filtering support
+    pub anisotropy: Option<f32>,
+    /// Used by [`WrapMode::ClampToBorder`]
+    pub border_color: [f32; 4],
+}
+
+/// A GL sampler object, overrides the sampling state of whatever texture is bound to its unit.
+pub struct Sampler {
+    gl: Rc<glow::Context>,
+    sampler: glow::NativeSampler,
+}
+
+impl SamplerDesc {
+    /// Nearest filtering on every axis, for pixel art and UI atlases.
+    pub fn nearest() -> Self {
+        SamplerDesc {
+            min_filter: Filter::Nearest,
+            mag_filter: Filter::Nearest,
+            mipmap_filter: Some(Filter::Nearest),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_wrap(self, wrap: WrapMode) -> Self {
+        SamplerDesc {
+            wrap_s: wrap,
+            wrap_t: wrap,
+            wrap_r: wrap,
+            ..self
+        }
+    }
+
+    pub(crate) fn min_filter_enum(&self) -> u32 {
+        match (self.min_filter, self.mipmap_filter) {
+            (Filter::Nearest, None) => glow::NEAREST,
+            (Filter::Linear, None) => glow::LINEAR,
+            (Filter::Nearest, Some(Filter::Nearest)) => glow::NEAREST_MIPMAP_NEAREST,
+            (Filter::Nearest, Some(Filter::Linear)) => glow::NEAREST_MIPMAP_LINEAR,
+            (Filter::Linear, Some(Filter::Nearest)) => glow::LINEAR_MIPMAP_NEAREST,
+            (Filter::Linear, Some(Filter::Linear)) => glow::LINEAR_MIPMAP_LINEAR,
+        }
+    }
+
+    pub(crate) fn mag_filter_enum(&self) -> u32 {
+        match self.mag_filter {
+            Filter::Nearest => glow::NEAREST,
+            Filter::Linear => glow::LINEAR,
+        }
+    }
+
+    /// Sets the sampling parameters of the texture bound to `target`.
+    pub(crate) fn apply_to_texture(&self, gl: &glow::Context, target: u32) {
+        unsafe {
+            gl.tex_parameter_i32(target, glow::TEXTURE_WRAP_S, wrap_enum(self.wrap_s) as i32);
+            gl.tex_parameter_i32(target, glow::TEXTURE_WRAP_T, wrap_enum(self.wrap_t) as i32);
+            gl.tex_parameter_i32(target, glow::TEXTURE_WRAP_R, wrap_enum(self.wrap_r) as i32);
+            gl.tex_parameter_i32(
+                target,
+                glow::TEXTURE_MIN_FILTER,
+                self.min_filter_enum() as i32,
+            );
+            gl.tex_parameter_i32(
+                target,
+                glow::TEXTURE_MAG_FILTER,
+                self.mag_filter_enum() as i32,
+            );
+            gl.tex_parameter_f32_slice(target, glow::TEXTURE_BORDER_COLOR, &self.border_color);
+
+            if let Some(anisotropy) = self.anisotropy(gl) {
+                gl.tex_parameter_f32(target, glow::TEXTURE_MAX_ANISOTROPY, anisotropy);
+            }
+        }
+    }
+
+    fn anisotropy(&self, gl: &glow::Context) -> Option<f32> {
+        let anisotropy = self.anisotropy?;
+
+        let extensions = gl.supported_extensions();
+        let supported = extensions.contains("GL_EXT_texture_filter_anisotropic")
+            || extensions.contains("GL_ARB_texture_filter_anisotropic")
+            || gl.version().major > 4
+            || (gl.version().major == 4 && gl.version().minor >= 6);
+        if !supported {
+            return None;
+        }
+
+        let max = unsafe { gl.get_parameter_f32(glow::MAX_TEXTURE_MAX_ANISOTROPY) };
+        Some(anisotropy.clamp(1.0, max.max(1.0)))
+    }
+}
+
+impl Default for SamplerDesc {
+    fn default() -> Self {
+        SamplerDesc {
+            wrap_s: WrapMode::Repeat,
+            wrap_t: WrapMode::Repeat,
+            wrap_r: WrapMode::Repeat,
+            min_filter: Filter::Linear,
+            mag_filter: Filter::Linear,
+            mipmap_filter: Some(Filter::Linear),
+            anisotropy: None,
+            border_color: [0.0; 4],
+        }
+    }
+}
+
+impl Sampler {
+    pub fn new(gl: Rc<glow::Context>, desc: &SamplerDesc) -> Result<Self, String> {
+        unsafe {
+            let sampler = gl.create_sampler()?;
+
+            gl.sampler_parameter_i32(sampler, glow::TEXTURE_WRAP_S, wrap_enum(desc.wrap_s) as i32);
+            gl.sampler_parameter_i32(sampler, glow::TEXTURE_WRAP_T, wrap_enum(desc.wrap_t) as i32);
+            gl.sampler_parameter_i32(sampler, glow::TEXTURE_WRAP_R, wrap_enum(desc.wrap_r) as i32);
+            gl.sampler_parameter_i32(
+                sampler,
+                glow::TEXTURE_MIN_FILTER,
+                desc.min_filter_enum() as i32,
+            );
+            gl.sampler_parameter_i32(
+                sampler,
+                glow::TEXTURE_MAG_FILTER,
+                desc.mag_filter_enum() as i32,
+            );
+            gl.sampler_parameter_f32_slice(sampler, glow::TEXTURE_BORDER_COLOR, &desc.border_color);
+
+            if let Some(anisotropy) = desc.anisotropy(&gl) {
+                gl.sampler_parameter_f32(sampler, glow::TEXTURE_MAX_ANISOTROPY, anisotropy);
+            }
+
+            Ok(Sampler { gl, sampler })
+        }
+    }
+
+    /// Binds the sampler to texture unit `unit` (0 for `TEXTURE0`).
+    pub fn bind(&self, unit: u32) {
+        unsafe { self.gl.bind_sampler(unit, Some(self.sampler)) }
+    }
+
+    /// Restores the bound texture's own sampling state on `unit`.
+    pub fn unbind(gl: &glow::Context, unit: u32) {
+        unsafe { gl.bind_sampler(unit, None) }
+    }
+}
+
+impl Drop for Sampler {
+    fn drop(&mut self) {
+        unsafe { self.gl.delete_sampler(self.sampler) }
+    }
+}
+
+fn wrap_enum(wrap: WrapMode) -> u32 {
+    match wrap {
+        WrapMode::Repeat => glow::REPEAT,
+        WrapMode::MirroredRepeat => glow::MIRRORED_REPEAT,
+        WrapMode::ClampToEdge => glow::CLAMP_TO_EDGE,
+        WrapMode::ClampToBorder => glow::CLAMP_TO_BORDER,
+    }
+}
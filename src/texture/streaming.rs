@@ -0,0 +1,240 @@
+use std::rc::Rc;
+
+use glow::HasContext;
+use thiserror::Error;
+
+use super::{format::with_row_alignment, Texture};
+
+#[derive(Error, Debug)]
+pub enum UpdateError {
+    #[error(
+        "Region {width}x{height} at ({x}, {y}) is outside the {texture_width}x{texture_height} texture"
+    )]
+    RegionOutOfBounds {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        texture_width: u32,
+        texture_height: u32,
+    },
+
+    #[error("Expected {expected} bytes of pixel data, got {actual}")]
+    PixelDataSizeMismatch { expected: usize, actual: usize },
+
+    #[error("Compressed textures can't be updated")]
+    CompressedTexture,
+
+    #[error("Failed to map pixel buffer")]
+    BufferMappingFailed,
+}
+
+/// Streams pixel data into a texture through two pixel buffer objects.
+///
+/// Each upload is copied into the buffer the previous upload didn't use, so the copy doesn't
+/// wait on a transfer still reading from the other one. The texture is filled from the buffer in
+/// the same call, it isn't deferred to a later upload.
+pub struct TextureStreamer {
+    gl: Rc<glow::Context>,
+    buffers: [glow::NativeBuffer; 2],
+    next: usize,
+    size: usize,
+}
+
+impl Texture {
+    /// Replaces the pixels of a region of the full size level.
+    ///
+    /// `data` holds tightly packed rows in the layout of [`super::TextureFormat::pixel_data`],
+    /// the first row at `y`, which counts up from `t = 0`. Lower mip levels keep their old
+    /// pixels, call [`Texture::regenerate_mipmaps`] once the updates of a frame are done.
+    ///
+    /// This binds the texture to the active texture unit.
+    pub fn update_region(
+        &self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        data: &[u8],
+    ) -> Result<(), UpdateError> {
+        self.check_region(x, y, width, height, data.len())?;
+
+        unsafe {
+            self.bind();
+            self.sub_image(x, y, width, height, glow::PixelUnpackData::Slice(data));
+        }
+        Ok(())
+    }
+
+    /// Rebuilds the lower mip levels from the full size level with the driver, even ones made
+    /// with [`super::Mipmaps::Cpu`]. Does nothing for textures without mip levels.
+    ///
+    /// This binds the texture to the active texture unit.
+    pub fn regenerate_mipmaps(&self) -> Result<(), UpdateError> {
+        if self.compressed.is_some() {
+            return Err(UpdateError::CompressedTexture);
+        }
+        if self.levels > 1 {
+            unsafe {
+                self.bind();
+                self.gl.generate_mipmap(glow::TEXTURE_2D);
+            }
+        }
+        Ok(())
+    }
+
+    fn check_region(
+        &self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        len: usize,
+    ) -> Result<(), UpdateError> {
+        if self.compressed.is_some() {
+            return Err(UpdateError::CompressedTexture);
+        }
+
+        let fits =
+            |start: u32, size: u32, max: u32| start.checked_add(size).is_some_and(|end| end <= max);
+        if !fits(x, width, self.width) || !fits(y, height, self.height) {
+            return Err(UpdateError::RegionOutOfBounds {
+                x,
+                y,
+                width,
+                height,
+                texture_width: self.width,
+                texture_height: self.height,
+            });
+        }
+
+        let expected = width as usize * height as usize * self.format.pixel_size();
+        if len != expected {
+            return Err(UpdateError::PixelDataSizeMismatch {
+                expected,
+                actual: len,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Uploads a checked region into the bound texture.
+    unsafe fn sub_image(
+        &self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        pixels: glow::PixelUnpackData,
+    ) {
+        with_row_alignment(&self.gl, self.format, || {
+            self.gl.tex_sub_image_2d(
+                glow::TEXTURE_2D,
+                0,
+                x as i32,
+                y as i32,
+                width as i32,
+                height as i32,
+                self.format.pixel_format(),
+                self.format.pixel_type(),
+                pixels,
+            )
+        });
+    }
+}
+
+impl TextureStreamer {
+    /// Creates buffers big enough to replace all of `texture`, or any texture of its size and
+    /// format.
+    pub fn new(gl: Rc<glow::Context>, texture: &Texture) -> Result<Self, String> {
+        let size = texture.width as usize * texture.height as usize * texture.format.pixel_size();
+
+        unsafe {
+            let first = gl.create_buffer()?;
+            let second = match gl.create_buffer() {
+                Ok(buffer) => buffer,
+                Err(error_message) => {
+                    gl.delete_buffer(first);
+                    return Err(error_message);
+                }
+            };
+
+            for buffer in [first, second] {
+                gl.bind_buffer(glow::PIXEL_UNPACK_BUFFER, Some(buffer));
+                gl.buffer_data_size(glow::PIXEL_UNPACK_BUFFER, size as i32, glow::STREAM_DRAW);
+            }
+            gl.bind_buffer(glow::PIXEL_UNPACK_BUFFER, None);
+
+            Ok(TextureStreamer {
+                gl,
+                buffers: [first, second],
+                next: 0,
+                size,
+            })
+        }
+    }
+
+    /// Replaces all of `texture`, see [`Texture::update_region`] for the layout of `data`.
+    pub fn upload(&mut self, texture: &Texture, data: &[u8]) -> Result<(), UpdateError> {
+        self.upload_region(texture, 0, 0, texture.width, texture.height, data)
+    }
+
+    /// Replaces a region of `texture`, see [`Texture::update_region`] for the layout of `data`.
+    pub fn upload_region(
+        &mut self,
+        texture: &Texture,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        data: &[u8],
+    ) -> Result<(), UpdateError> {
+        texture.check_region(x, y, width, height, data.len())?;
+        if data.len() > self.size {
+            return Err(UpdateError::PixelDataSizeMismatch {
+                expected: self.size,
+                actual: data.len(),
+            });
+        }
+
+        let buffer = self.buffers[self.next];
+        self.next = 1 - self.next;
+
+        unsafe {
+            self.gl.bind_buffer(glow::PIXEL_UNPACK_BUFFER, Some(buffer));
+
+            // Invalidating lets the driver hand out fresh storage instead of waiting for the GPU
+            let mapping = self.gl.map_buffer_range(
+                glow::PIXEL_UNPACK_BUFFER,
+                0,
+                data.len() as i32,
+                glow::MAP_WRITE_BIT | glow::MAP_INVALIDATE_BUFFER_BIT,
+            );
+            if mapping.is_null() {
+                self.gl.bind_buffer(glow::PIXEL_UNPACK_BUFFER, None);
+                return Err(UpdateError::BufferMappingFailed);
+            }
+            std::ptr::copy_nonoverlapping(data.as_ptr(), mapping, data.len());
+            self.gl.unmap_buffer(glow::PIXEL_UNPACK_BUFFER);
+
+            texture.bind();
+            texture.sub_image(x, y, width, height, glow::PixelUnpackData::BufferOffset(0));
+
+            // Left bound, every later pixel upload would read from the buffer
+            self.gl.bind_buffer(glow::PIXEL_UNPACK_BUFFER, None);
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for TextureStreamer {
+    fn drop(&mut self) {
+        unsafe {
+            for buffer in self.buffers {
+                self.gl.delete_buffer(buffer);
+            }
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::path::Path;
+
+use learn_opengl::{
+    material::{MaterialDescription, Parameter},
+    texture::{ColorSpace, TextureMeta},
+};
+
+fn parameters(source: &str) -> Vec<(String, Parameter)> {
+    let source = format!(
+        "vertex_shader = \"color.vert\"\nfragment_shader = \"color.frag\"\n[parameters]\n{source}"
+    );
+    let description: MaterialDescription = toml::from_str(&source).unwrap();
+    description.parameters.into_iter().collect()
+}
+
+#[test]
+fn whole_numbers_are_floats() {
+    assert_eq!(
+        parameters("\"material.shininess\" = 32"),
+        [("material.shininess".to_string(), Parameter::Float(32.0))]
+    );
+    assert_eq!(
+        parameters("\"material.shininess\" = 32.0"),
+        [("material.shininess".to_string(), Parameter::Float(32.0))]
+    );
+}
+
+#[test]
+fn ints_are_tagged() {
+    assert_eq!(
+        parameters("\"material.layer\" = { int = 2 }"),
+        [("material.layer".to_string(), Parameter::Int(2))]
+    );
+}
+
+#[test]
+fn parses_other_types() {
+    let parsed = parameters(
+        "blinn = true\nlight = [1, 0.5, 0]\nmodel = [1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1]",
+    );
+
+    assert_eq!(
+        parsed,
+        [
+            ("blinn".to_string(), Parameter::Bool(true)),
+            (
+                "light".to_string(),
+                Parameter::Vec3(glam::vec3(1.0, 0.5, 0.0))
+            ),
+            ("model".to_string(), Parameter::Mat4(glam::Mat4::IDENTITY)),
+        ]
+    );
+}
+
+#[test]
+fn rejects_unknown_tags() {
+    let source = "vertex_shader = \"a\"\nfragment_shader = \"b\"\n[parameters]\nx = { uint = 2 }";
+    assert!(toml::from_str::<MaterialDescription>(source).is_err());
+}
+
+#[test]
+fn textures_take_a_path_or_a_table() {
+    let source = r#"
+        vertex_shader = "color.vert"
+        fragment_shader = "color.frag"
+
+        [textures]
+        "material.diffuse" = { path = "diffuse.png", color_space = "srgb" }
+        "material.specular" = "specular.png"
+    "#;
+    let description: MaterialDescription = toml::from_str(source).unwrap();
+
+    let diffuse = &description.textures["material.diffuse"];
+    assert_eq!(diffuse.path, Path::new("diffuse.png"));
+    assert_eq!(diffuse.settings.color_space, Some(ColorSpace::Srgb));
+
+    let specular = &description.textures["material.specular"];
+    assert_eq!(specular.path, Path::new("specular.png"));
+    assert_eq!(specular.settings, TextureMeta::default());
+}
+
+#[test]
+fn rejects_unknown_texture_settings() {
+    let source = r#"
+        vertex_shader = "a"
+        fragment_shader = "b"
+        [textures]
+        diffuse = { path = "diffuse.png", colour_space = "srgb" }
+    "#;
+    assert!(toml::from_str::<MaterialDescription>(source).is_err());
+}
+
+#[test]
+fn shipped_material_loads_its_diffuse_map_as_srgb() {
+    let source = include_str!("../res/materials/container.toml");
+    let description: MaterialDescription = toml::from_str(source).unwrap();
+
+    assert_eq!(
+        description.textures["material.diffuse"]
+            .settings
+            .color_space,
+        Some(ColorSpace::Srgb)
+    );
+    assert_eq!(
+        description.textures["material.specular"]
+            .settings
+            .color_space,
+        None
+    );
+}
@@ -0,0 +1,50 @@
+use learn_opengl::mesh::{AttributeType, VertexLayout};
+
+#[test]
+fn packs_attributes_in_order() {
+    let layout = VertexLayout::new()
+        .attribute("aPos", 3, AttributeType::Float, false)
+        .attribute("aNormal", 3, AttributeType::Float, false)
+        .attribute("aTexCoords", 2, AttributeType::Float, false);
+
+    let placement: Vec<_> = layout
+        .attributes()
+        .iter()
+        .map(|attribute| {
+            (
+                attribute.name.as_str(),
+                attribute.location,
+                attribute.offset,
+            )
+        })
+        .collect();
+    assert_eq!(
+        placement,
+        [("aPos", 0, 0), ("aNormal", 1, 12), ("aTexCoords", 2, 24)]
+    );
+    assert_eq!(layout.stride(), 32);
+}
+
+#[test]
+fn mixes_component_types() {
+    let layout = VertexLayout::new()
+        .attribute("aPos", 3, AttributeType::Float, false)
+        .attribute("aColor", 4, AttributeType::UnsignedByte, true)
+        .attribute("aTexCoords", 2, AttributeType::UnsignedShort, true)
+        .attribute("aBone", 1, AttributeType::Int, false);
+
+    let offsets: Vec<_> = layout
+        .attributes()
+        .iter()
+        .map(|attribute| attribute.offset)
+        .collect();
+    assert_eq!(offsets, [0, 12, 16, 20]);
+    assert_eq!(layout.stride(), 24);
+    assert!(layout.attributes()[1].normalized);
+}
+
+#[test]
+#[should_panic]
+fn rejects_five_components() {
+    let _ = VertexLayout::new().attribute("aTooWide", 5, AttributeType::Float, false);
+}
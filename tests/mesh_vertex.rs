@@ -0,0 +1,67 @@
+use learn_opengl::mesh::{AttributeType, Vertex, VertexLayout};
+
+#[derive(Clone, Copy, Vertex)]
+#[repr(C)]
+struct LitVertex {
+    #[vertex(name = "aPos")]
+    position: glam::Vec3,
+    #[vertex(name = "aNormal")]
+    normal: [f32; 3],
+    #[vertex(name = "aTexCoords")]
+    tex_coords: glam::Vec2,
+}
+
+#[derive(Clone, Copy, Vertex)]
+#[repr(C)]
+struct SpriteVertex {
+    position: [f32; 2],
+    #[vertex(normalized)]
+    color: [u8; 4],
+    #[vertex(normalized)]
+    tex_coords: [u16; 2],
+    layer: f32,
+}
+
+#[test]
+fn matches_hand_written_layout() {
+    let expected = VertexLayout::new()
+        .attribute("aPos", 3, AttributeType::Float, false)
+        .attribute("aNormal", 3, AttributeType::Float, false)
+        .attribute("aTexCoords", 2, AttributeType::Float, false);
+
+    assert_eq!(LitVertex::layout(), expected);
+    assert_eq!(
+        LitVertex::layout().stride(),
+        std::mem::size_of::<LitVertex>()
+    );
+}
+
+#[test]
+fn reads_field_types_and_options() {
+    let layout = SpriteVertex::layout();
+    let attributes: Vec<_> = layout
+        .attributes()
+        .iter()
+        .map(|attribute| {
+            (
+                attribute.name.as_str(),
+                attribute.location,
+                attribute.components,
+                attribute.kind,
+                attribute.normalized,
+                attribute.offset,
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        attributes,
+        [
+            ("position", 0, 2, AttributeType::Float, false, 0),
+            ("color", 1, 4, AttributeType::UnsignedByte, true, 8),
+            ("tex_coords", 2, 2, AttributeType::UnsignedShort, true, 12),
+            ("layer", 3, 1, AttributeType::Float, false, 16),
+        ]
+    );
+    assert_eq!(layout.stride(), 20);
+}
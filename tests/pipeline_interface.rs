@@ -0,0 +1,172 @@
+use learn_opengl::pipeline::{check_interface, InterfaceVariable, PipelineError};
+
+fn variable(name: &str, ty: &str, location: Option<u32>) -> InterfaceVariable {
+    InterfaceVariable {
+        name: name.to_string(),
+        ty: ty.to_string(),
+        location,
+    }
+}
+
+#[test]
+fn ignores_comments() {
+    let source = "
+        #version 330 core
+        // out vec3 LineComment;
+        out vec2 TexCoords; // out vec3 Trailing;
+        /* out vec4 BlockComment;
+           out float AcrossLines; */
+        out vec3 Normal;
+    ";
+
+    assert_eq!(
+        InterfaceVariable::outputs(source),
+        [
+            variable("TexCoords", "vec2", None),
+            variable("Normal", "vec3", None),
+        ]
+    );
+}
+
+#[test]
+fn splits_multiple_declarators() {
+    let source = "flat out vec3 a, b[2],c;";
+
+    assert_eq!(
+        InterfaceVariable::outputs(source),
+        [
+            variable("a", "vec3", None),
+            variable("b", "vec3[2]", None),
+            variable("c", "vec3", None),
+        ]
+    );
+}
+
+#[test]
+fn reads_locations_among_other_layout_qualifiers() {
+    let source = "
+        layout(std140, location = 2) in vec4 Color;
+        layout (location=0) in vec3 Position;
+        layout(std140) in float Weight;
+    ";
+
+    assert_eq!(
+        InterfaceVariable::inputs(source),
+        [
+            variable("Color", "vec4", Some(2)),
+            variable("Position", "vec3", Some(0)),
+            variable("Weight", "float", None),
+        ]
+    );
+}
+
+#[test]
+fn matches_interface_blocks_by_block_name() {
+    let source = "
+        out VS_OUT {
+            vec3 FragPos;
+            vec2 TexCoords;
+        } vs_out;
+
+        layout(location = 1) out Extra {
+            float depth;
+        };
+
+        void main() {
+            vs_out.FragPos = vec3(0.0);
+        }
+
+        out vec3 AfterMain;
+    ";
+
+    assert_eq!(
+        InterfaceVariable::outputs(source),
+        [
+            variable("VS_OUT", "block", None),
+            variable("Extra", "block", Some(1)),
+            variable("AfterMain", "vec3", None),
+        ]
+    );
+}
+
+#[test]
+fn skips_function_bodies_and_built_ins() {
+    let source = "
+        in vec3 Normal;
+        out vec4 FragColor;
+        vec3 shade(in vec3 normal) { return normal; }
+        void main() { out_of_scope: ; }
+        out vec4 gl_Position;
+    ";
+
+    assert_eq!(
+        InterfaceVariable::inputs(source),
+        [variable("Normal", "vec3", None)]
+    );
+    assert_eq!(
+        InterfaceVariable::outputs(source),
+        [variable("FragColor", "vec4", None)]
+    );
+}
+
+#[test]
+fn matching_interface_passes() {
+    let outputs = [
+        variable("Normal", "vec3", None),
+        variable("vertexColor", "vec4", Some(3)),
+    ];
+    let inputs = [
+        variable("Normal", "vec3", None),
+        variable("fragmentColor", "vec4", Some(3)),
+    ];
+
+    assert!(check_interface(&outputs, &inputs).is_ok());
+}
+
+#[test]
+fn reports_mismatched_types() {
+    let outputs = [variable("Normal", "vec3", None)];
+    let inputs = [variable("Normal", "vec4", None)];
+
+    assert!(matches!(
+        check_interface(&outputs, &inputs),
+        Err(PipelineError::InterfaceMismatch { ref output_type, ref input_type, .. })
+            if output_type == "vec3" && input_type == "vec4"
+    ));
+}
+
+#[test]
+fn reports_missing_outputs() {
+    let outputs = [variable("Normal", "vec3", Some(0))];
+    let inputs = [variable("Normal", "vec3", Some(1))];
+
+    assert!(matches!(
+        check_interface(&outputs, &inputs),
+        Err(PipelineError::MissingOutput { .. })
+    ));
+}
+
+#[test]
+fn reports_location_on_one_side_only() {
+    let outputs = [variable("Normal", "vec3", Some(0))];
+    let inputs = [variable("Normal", "vec3", None)];
+    assert!(matches!(
+        check_interface(&outputs, &inputs),
+        Err(PipelineError::LocationMismatch {
+            output_location: Some(0),
+            input_location: None,
+            ..
+        })
+    ));
+
+    let outputs = [variable("Normal", "vec3", None)];
+    let inputs = [variable("Normal", "vec3", Some(0))];
+    assert!(matches!(
+        check_interface(&outputs, &inputs),
+        Err(PipelineError::LocationMismatch {
+            output_location: None,
+            input_location: Some(0),
+            ..
+        })
+    ));
+}
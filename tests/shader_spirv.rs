@@ -0,0 +1,108 @@
+use learn_opengl::{
+    shader::ShaderType,
+    spirv::{Specialization, SpirvError, SpirvModule},
+};
+
+const MAGIC_NUMBER: u32 = 0x0723_0203;
+
+/// A SPIR-V 1.0 module declaring the Shader capability and a vertex entry point called `main`.
+fn vertex_module() -> Vec<u32> {
+    let name = u32::from_le_bytes(*b"main");
+    vec![
+        // Header: magic, version, generator, bound, schema
+        MAGIC_NUMBER,
+        0x0001_0000,
+        0,
+        2,
+        0,
+        // OpCapability Shader
+        2 << 16 | 17,
+        1,
+        // OpEntryPoint Vertex %1 "main"
+        5 << 16 | 15,
+        0,
+        1,
+        name,
+        0,
+    ]
+}
+
+#[test]
+fn parses_entry_points() {
+    let module = SpirvModule::from_words(vertex_module()).unwrap();
+
+    assert!(module.has_entry_point("main", ShaderType::VertexShader));
+    assert!(!module.has_entry_point("main", ShaderType::FragmentShader));
+    assert_eq!(module.words(), vertex_module());
+}
+
+#[test]
+fn rejects_bad_magic() {
+    let mut words = vertex_module();
+    words[0] = 0xdead_beef;
+
+    assert!(matches!(
+        SpirvModule::from_words(words),
+        Err(SpirvError::InvalidMagicNumber { magic: 0xdead_beef })
+    ));
+}
+
+#[test]
+fn swaps_modules_of_the_other_endianness() {
+    let swapped = vertex_module().into_iter().map(u32::swap_bytes).collect();
+    let module = SpirvModule::from_words(swapped).unwrap();
+
+    assert_eq!(module.words(), vertex_module());
+    assert!(module.has_entry_point("main", ShaderType::VertexShader));
+}
+
+#[test]
+fn rejects_truncated_header() {
+    let words = vertex_module()[..4].to_vec();
+    assert!(matches!(
+        SpirvModule::from_words(words),
+        Err(SpirvError::TruncatedHeader)
+    ));
+
+    let bytes: Vec<u8> = vertex_module()
+        .iter()
+        .flat_map(|word| word.to_ne_bytes())
+        .collect();
+    assert!(matches!(
+        SpirvModule::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(SpirvError::InvalidLength { .. })
+    ));
+}
+
+#[test]
+fn reports_missing_entry_point() {
+    let module = SpirvModule::from_words(vertex_module()).unwrap();
+
+    let error = module
+        .validate(ShaderType::FragmentShader, &Specialization::default())
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        SpirvError::MissingEntryPoint { ref name, stage: ShaderType::FragmentShader } if name == "main"
+    ));
+
+    let error = module
+        .validate(
+            ShaderType::VertexShader,
+            &Specialization::new("vertex_main"),
+        )
+        .unwrap_err();
+    assert!(matches!(error, SpirvError::MissingEntryPoint { .. }));
+}
+
+#[test]
+fn rejects_unknown_specialization_constant() {
+    let module = SpirvModule::from_words(vertex_module()).unwrap();
+    let mut specialization = Specialization::default();
+    specialization.set_float(3, 1.0);
+
+    assert!(matches!(
+        module.validate(ShaderType::VertexShader, &specialization),
+        Err(SpirvError::UnknownSpecializationConstant { id: 3 })
+    ));
+}
@@ -0,0 +1,108 @@
+use image::{
+    codecs::gif::GifEncoder, Delay, DynamicImage, Frame, GenericImageView, Rgba, RgbaImage,
+};
+use learn_opengl::texture::{load_frames, FrameTimer};
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 1e-4, "{actual} != {expected}");
+}
+
+#[test]
+fn timer_steps_through_frames() {
+    let mut timer = FrameTimer::new(&[0.1, 0.2, 0.1]);
+    assert_close(timer.duration(), 0.4);
+    assert_eq!(timer.frame(), 0);
+
+    timer.update(0.05);
+    assert_eq!(timer.frame(), 0);
+    timer.update(0.1);
+    assert_eq!(timer.frame(), 1);
+    timer.update(0.1);
+    assert_eq!(timer.frame(), 1);
+    timer.update(0.1);
+    assert_eq!(timer.frame(), 2);
+}
+
+#[test]
+fn timer_loops_by_default() {
+    let mut timer = FrameTimer::new(&[0.1, 0.1]);
+
+    timer.update(0.25);
+    assert_eq!(timer.frame(), 0);
+    assert_close(timer.elapsed(), 0.05);
+
+    // Long hitches skip whole passes
+    timer.update(10.15);
+    assert_eq!(timer.frame(), 1);
+    assert!(!timer.is_finished());
+}
+
+#[test]
+fn timer_without_looping_holds_the_last_frame() {
+    let mut timer = FrameTimer::new(&[0.1, 0.1, 0.1]);
+    timer.set_looping(false);
+
+    timer.update(1.0);
+    assert_eq!(timer.frame(), 2);
+    assert!(timer.is_finished());
+
+    timer.restart();
+    assert_eq!(timer.frame(), 0);
+    assert!(!timer.is_finished());
+}
+
+#[test]
+fn timer_skips_zero_length_frames() {
+    let mut timer = FrameTimer::new(&[0.1, 0.0, 0.1]);
+    timer.update(0.1);
+    assert_eq!(timer.frame(), 2);
+
+    let mut empty = FrameTimer::new(&[]);
+    empty.update(1.0);
+    assert_eq!(empty.frame(), 0);
+}
+
+#[test]
+fn loads_gif_frames_with_delays() {
+    let colors = [Rgba([255, 0, 0, 255]), Rgba([0, 0, 255, 255])];
+    let path = std::env::temp_dir().join(format!("animated-{}.gif", std::process::id()));
+
+    {
+        let file = std::fs::File::create(&path).unwrap();
+        let mut encoder = GifEncoder::new(file);
+        for (color, delay) in colors.iter().zip([50, 0]) {
+            let frame = Frame::from_parts(
+                RgbaImage::from_pixel(4, 2, *color),
+                0,
+                0,
+                Delay::from_numer_denom_ms(delay, 1),
+            );
+            encoder.encode_frame(frame).unwrap();
+        }
+    }
+
+    let frames = load_frames(&path);
+    std::fs::remove_file(&path).unwrap();
+    let frames = frames.unwrap();
+
+    assert_eq!(frames.len(), 2);
+    for ((image, _), color) in frames.iter().zip(colors) {
+        assert_eq!(image.dimensions(), (4, 2));
+        assert_eq!(image.get_pixel(0, 0), color);
+    }
+    assert_close(frames[0].1, 0.05);
+    // A zero delay plays at the browser default
+    assert_close(frames[1].1, 0.1);
+}
+
+#[test]
+fn still_image_is_a_single_frame() {
+    let path = std::env::temp_dir().join(format!("still-{}.png", std::process::id()));
+    DynamicImage::ImageRgba8(RgbaImage::new(3, 3))
+        .save(&path)
+        .unwrap();
+
+    let frames = load_frames(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(frames.unwrap().len(), 1);
+}
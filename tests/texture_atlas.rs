@@ -0,0 +1,163 @@
+use image::{DynamicImage, Rgba, RgbaImage};
+use learn_opengl::texture::{AtlasLayout, AtlasOptions, AtlasRect, CreationError};
+
+/// Sizes from a fixed linear congruential sequence, the same on every run.
+fn sizes(count: usize) -> Vec<(String, u32, u32)> {
+    let mut state = 12345u32;
+    let mut next = || {
+        state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        (state >> 16) % 60 + 4
+    };
+    (0..count)
+        .map(|i| (format!("sprite{i}"), next(), next()))
+        .collect()
+}
+
+fn pack(sizes: &[(String, u32, u32)], options: &AtlasOptions) -> AtlasLayout {
+    let sizes: Vec<_> = sizes
+        .iter()
+        .map(|(name, width, height)| (name.as_str(), *width, *height))
+        .collect();
+    AtlasLayout::pack(&sizes, options).unwrap()
+}
+
+fn overlaps(a: AtlasRect, b: AtlasRect, padding: u32) -> bool {
+    a.x < b.x + b.width + padding
+        && b.x < a.x + a.width + padding
+        && a.y < b.y + b.height + padding
+        && b.y < a.y + a.height + padding
+}
+
+#[test]
+fn packs_without_overlap() {
+    let sizes = sizes(80);
+    let options = AtlasOptions {
+        padding: 2,
+        ..Default::default()
+    };
+    let layout = pack(&sizes, &options);
+
+    assert_eq!(layout.rects().len(), sizes.len());
+    for (name, width, height) in &sizes {
+        let rect = layout.rect(name).unwrap();
+        assert_eq!((rect.width, rect.height), (*width, *height));
+        assert!(rect.x >= 2 && rect.y >= 2);
+        assert!(rect.x + rect.width + 2 <= layout.width());
+        assert!(rect.y + rect.height + 2 <= layout.height());
+    }
+
+    let rects: Vec<_> = layout.rects().values().copied().collect();
+    for (i, a) in rects.iter().enumerate() {
+        for b in &rects[i + 1..] {
+            // Two paddings apart, one from each rectangle
+            assert!(!overlaps(*a, *b, 4), "{a:?} and {b:?} overlap");
+        }
+    }
+}
+
+#[test]
+fn packing_is_deterministic() {
+    let sizes = sizes(40);
+    let options = AtlasOptions::default();
+    let layout = pack(&sizes, &options);
+
+    let mut reversed = sizes.clone();
+    reversed.reverse();
+    assert_eq!(pack(&sizes, &options), layout);
+    assert_eq!(pack(&reversed, &options), layout);
+}
+
+#[test]
+fn grows_to_powers_of_two() {
+    let options = AtlasOptions {
+        padding: 0,
+        ..Default::default()
+    };
+
+    let layout = pack(&[("a".to_string(), 16, 16)], &options);
+    assert_eq!((layout.width(), layout.height()), (16, 16));
+
+    // Four 16x16 squares fill a 32x32 atlas exactly
+    let squares: Vec<_> = (0..4).map(|i| (i.to_string(), 16, 16)).collect();
+    let layout = pack(&squares, &options);
+    assert_eq!((layout.width(), layout.height()), (32, 32));
+
+    let layout = pack(&[("wide".to_string(), 100, 10)], &options);
+    assert!(layout.width().is_power_of_two() && layout.height().is_power_of_two());
+    assert!(layout.width() >= 100);
+}
+
+#[test]
+fn uv_rects_cover_images() {
+    let options = AtlasOptions {
+        padding: 0,
+        ..Default::default()
+    };
+    let layout = pack(
+        &[("a".to_string(), 32, 16), ("b".to_string(), 32, 16)],
+        &options,
+    );
+    assert_eq!((layout.width(), layout.height()), (32, 32));
+
+    let a = layout.uv_rect("a").unwrap();
+    let b = layout.uv_rect("b").unwrap();
+    assert_eq!(a.min, glam::vec2(0.0, 0.0));
+    assert_eq!(a.max, glam::vec2(1.0, 0.5));
+    assert_eq!(b.min, glam::vec2(0.0, 0.5));
+    assert_eq!(b.max, glam::vec2(1.0, 1.0));
+    assert!(layout.uv_rect("c").is_none());
+}
+
+#[test]
+fn compose_copies_images_and_extends_edges() {
+    let red = Rgba([255, 0, 0, 255]);
+    let green = Rgba([0, 255, 0, 255]);
+    let image =
+        DynamicImage::ImageRgba8(RgbaImage::from_fn(
+            2,
+            2,
+            |x, _| {
+                if x == 0 {
+                    red
+                } else {
+                    green
+                }
+            },
+        ));
+
+    let layout = AtlasLayout::pack(&[("image", 2, 2)], &AtlasOptions::default()).unwrap();
+    let atlas = layout.compose(&[("image", image)]).to_rgba8();
+    let rect = layout.rect("image").unwrap();
+    assert_eq!((rect.x, rect.y), (1, 1));
+
+    assert_eq!(atlas[(1, 1)], red);
+    assert_eq!(atlas[(2, 2)], green);
+    // The padding repeats the nearest edge pixel
+    assert_eq!(atlas[(0, 0)], red);
+    assert_eq!(atlas[(3, 1)], green);
+}
+
+#[test]
+fn rejects_duplicates_and_oversized_images() {
+    let options = AtlasOptions {
+        padding: 0,
+        max_size: 64,
+    };
+
+    assert!(matches!(
+        AtlasLayout::pack(&[("a", 4, 4), ("a", 8, 8)], &options),
+        Err(CreationError::DuplicateAtlasEntry { name }) if name == "a"
+    ));
+    assert!(matches!(
+        AtlasLayout::pack(&[("big", 65, 1)], &options),
+        Err(CreationError::AtlasTooSmall { max_size: 64 })
+    ));
+
+    // Five 32x32 squares don't fit in 64x64
+    let squares: Vec<_> = (0..5).map(|i| (i.to_string(), 32, 32)).collect();
+    let squares: Vec<_> = squares
+        .iter()
+        .map(|(name, width, height)| (name.as_str(), *width, *height))
+        .collect();
+    assert!(AtlasLayout::pack(&squares, &options).is_err());
+}
@@ -0,0 +1,247 @@
+use learn_opengl::texture::{ColorSpace, CompressedFormat, CompressedImage, ContainerError};
+
+// Both files hold the same 8x8 BC1 image with a full mip chain. Level 0 is red, green, blue and
+// white 4x4 blocks in reading order, the smaller levels are a single gray block.
+const KTX2: &[u8] = include_bytes!("data/bc1_mips.ktx2");
+const DDS: &[u8] = include_bytes!("data/bc1_mips.dds");
+
+#[test]
+fn parses_ktx2_mip_chain() {
+    let image = CompressedImage::from_ktx2(KTX2).unwrap();
+
+    assert_eq!(image.format(), CompressedFormat::Bc1);
+    assert_eq!(image.color_space(), ColorSpace::Srgb);
+    assert_eq!((image.width(), image.height()), (8, 8));
+
+    let sizes: Vec<_> = image.levels().iter().map(Vec::len).collect();
+    assert_eq!(sizes, [32, 8, 8, 8]);
+    assert_eq!(image.level_dimensions(3), (1, 1));
+}
+
+#[test]
+fn parses_dds_mip_chain() {
+    let image = CompressedImage::from_dds(DDS, ColorSpace::Linear).unwrap();
+
+    assert_eq!(image.format(), CompressedFormat::Bc1);
+    assert_eq!((image.width(), image.height()), (8, 8));
+
+    let sizes: Vec<_> = image.levels().iter().map(Vec::len).collect();
+    assert_eq!(sizes, [32, 8, 8, 8]);
+}
+
+#[test]
+fn legacy_dds_uses_given_color_space() {
+    let linear = CompressedImage::from_dds(DDS, ColorSpace::Linear).unwrap();
+    let srgb = CompressedImage::from_dds(DDS, ColorSpace::Srgb).unwrap();
+
+    assert_eq!(linear.color_space(), ColorSpace::Linear);
+    assert_eq!(srgb.color_space(), ColorSpace::Srgb);
+}
+
+#[test]
+fn both_containers_hold_the_same_data() {
+    let ktx2 = CompressedImage::parse(KTX2, ColorSpace::Linear).unwrap();
+    let dds = CompressedImage::parse(DDS, ColorSpace::Linear).unwrap();
+
+    assert_eq!(ktx2.levels(), dds.levels());
+}
+
+#[test]
+fn decompresses_bc1_blocks() {
+    let image = CompressedImage::from_ktx2(KTX2).unwrap();
+    let level = image.decompress(0).unwrap().to_rgb8();
+
+    assert_eq!(level.dimensions(), (8, 8));
+    assert_eq!(level.get_pixel(0, 0).0, [255, 0, 0]);
+    assert_eq!(level.get_pixel(7, 0).0, [0, 255, 0]);
+    assert_eq!(level.get_pixel(0, 7).0, [0, 0, 255]);
+    assert_eq!(level.get_pixel(7, 7).0, [255, 255, 255]);
+}
+
+#[test]
+fn decompresses_levels_smaller_than_a_block() {
+    let image = CompressedImage::from_dds(DDS, ColorSpace::Linear).unwrap();
+
+    for level in 1..4 {
+        let decoded = image.decompress(level).unwrap();
+        assert_eq!(
+            (decoded.width(), decoded.height()),
+            image.level_dimensions(level)
+        );
+    }
+}
+
+#[test]
+fn rejects_truncated_files() {
+    assert!(matches!(
+        CompressedImage::from_ktx2(&KTX2[..KTX2.len() - 4]),
+        Err(ContainerError::LevelOutOfBounds { level: 0, .. })
+    ));
+    assert!(matches!(
+        CompressedImage::from_ktx2(&KTX2[..64]),
+        Err(ContainerError::Ktx2ParsingFailed { .. })
+    ));
+    assert!(matches!(
+        CompressedImage::from_dds(&DDS[..DDS.len() - 4], ColorSpace::Linear),
+        Err(ContainerError::LevelSizeMismatch { level: 3, .. })
+    ));
+    assert!(matches!(
+        CompressedImage::parse(b"not a texture", ColorSpace::Linear),
+        Err(ContainerError::UnknownContainer)
+    ));
+}
+
+#[test]
+fn level_sizes_round_up_to_whole_blocks() {
+    assert_eq!(CompressedFormat::Bc1.level_size(1, 1), 8);
+    assert_eq!(CompressedFormat::Bc3.level_size(5, 5), 4 * 16);
+
+    let astc = CompressedFormat::Astc {
+        block_width: 6,
+        block_height: 5,
+    };
+    assert_eq!(astc.block_size(), (6, 5));
+    assert_eq!(astc.level_size(13, 10), 3 * 2 * 16);
+}
+
+#[test]
+fn picks_gl_internal_formats() {
+    assert_eq!(
+        CompressedFormat::Bc1.internal_format(ColorSpace::Srgb),
+        glow::COMPRESSED_SRGB_S3TC_DXT1_EXT
+    );
+    assert_eq!(
+        CompressedFormat::Bc7.internal_format(ColorSpace::Linear),
+        glow::COMPRESSED_RGBA_BPTC_UNORM
+    );
+
+    let astc = CompressedFormat::Astc {
+        block_width: 12,
+        block_height: 12,
+    };
+    assert_eq!(
+        astc.internal_format(ColorSpace::Srgb),
+        glow::COMPRESSED_SRGB8_ALPHA8_ASTC_12x12_KHR
+    );
+}
+
+#[test]
+fn rejects_more_levels_than_the_image_has() {
+    // dwMipMapCount of the DDS header
+    let mut dds = DDS.to_vec();
+    dds[28..32].copy_from_slice(&40u32.to_le_bytes());
+
+    assert!(matches!(
+        CompressedImage::from_dds(&dds, ColorSpace::Linear),
+        Err(ContainerError::TooManyLevels {
+            levels: 40,
+            max: 4,
+            ..
+        })
+    ));
+
+    let block = [0; 8];
+    assert!(matches!(
+        CompressedImage::new(
+            CompressedFormat::Bc4,
+            ColorSpace::Linear,
+            1,
+            1,
+            &[&block, &block]
+        ),
+        Err(ContainerError::TooManyLevels { max: 1, .. })
+    ));
+}
+
+fn decompress_block(format: CompressedFormat, width: u32, height: u32, block: &[u8]) -> Vec<u8> {
+    let image = CompressedImage::new(format, ColorSpace::Linear, width, height, &[block]).unwrap();
+    assert!(format.can_decompress());
+    image.decompress(0).unwrap().into_bytes()
+}
+
+#[test]
+fn decompresses_bc4_and_bc5_channels() {
+    // Endpoints 255 and 0, first pixel on index 0, second on 1, third on 2, the rest on 0
+    let red = [255, 0, 0b10_001_000, 0, 0, 0, 0, 0];
+    let green = [0, 255, 0, 0, 0, 0, 0, 0];
+
+    let bc4 = decompress_block(CompressedFormat::Bc4, 4, 1, &red);
+    assert_eq!(bc4, [255, 0, 218, 255]);
+
+    let bc5 = decompress_block(CompressedFormat::Bc5, 2, 1, &[red, green].concat());
+    assert_eq!(bc5, [255, 0, 0, 0]);
+}
+
+#[test]
+fn decompresses_etc2_individual_mode() {
+    // Left half red, right half black, with the smallest positive modifier everywhere
+    let block = [0xf0, 0x00, 0x00, 0x00, 0, 0, 0, 0];
+    let pixels = decompress_block(CompressedFormat::Etc2Rgb8, 4, 1, &block);
+
+    assert_eq!(pixels, [255, 2, 2, 255, 2, 2, 2, 2, 2, 2, 2, 2]);
+}
+
+#[test]
+fn decompresses_etc2_planar_mode() {
+    // Blue base 0 with a delta of -4 overflows into planar mode, every color is zero
+    let block = [0x00, 0x00, 0x04, 0x02, 0, 0, 0, 0];
+    let pixels = decompress_block(CompressedFormat::Etc2Rgb8, 1, 1, &block);
+
+    assert_eq!(pixels, [0, 0, 0]);
+}
+
+#[test]
+fn decompresses_eac_channels() {
+    // Base 200, multiplier 1, table 0 and index 4 for every pixel, a modifier of +2
+    let alpha = [200, 0x10, 0x92, 0x49, 0x24, 0x92, 0x49, 0x24];
+    let color = [0xf0, 0xf0, 0xf0, 0x00, 0, 0, 0, 0];
+    let rgba = decompress_block(CompressedFormat::Etc2Rgba8, 1, 1, &[alpha, color].concat());
+    assert_eq!(rgba, [255, 255, 255, 202]);
+
+    // Base 128, multiplier 0 and index 0, 128 * 8 + 4 - 3 in 11 bits
+    let red = [128, 0x00, 0, 0, 0, 0, 0, 0];
+    let r11 = decompress_block(CompressedFormat::EacR11, 1, 1, &red);
+    assert_eq!(r11, [128]);
+}
+
+#[test]
+fn bptc_and_astc_are_not_decompressed() {
+    let astc = CompressedFormat::Astc {
+        block_width: 4,
+        block_height: 4,
+    };
+    for format in [CompressedFormat::Bc6H, CompressedFormat::Bc7, astc] {
+        assert!(!format.can_decompress());
+
+        let block = [0; 16];
+        let image = CompressedImage::new(format, ColorSpace::Linear, 4, 4, &[&block]).unwrap();
+        assert!(matches!(
+            image.decompress(0),
+            Err(ContainerError::DecompressionUnsupported { .. })
+        ));
+    }
+}
+
+#[test]
+fn rejects_ktx2_levels_past_the_end() {
+    // Level 1 isn't stored last, so ktx2 itself doesn't check it
+    let level_1_length = 80 + 24 + 8;
+
+    let mut too_long = KTX2.to_vec();
+    too_long[level_1_length..level_1_length + 8].copy_from_slice(&1000u64.to_le_bytes());
+    assert!(matches!(
+        CompressedImage::from_ktx2(&too_long),
+        Err(ContainerError::LevelOutOfBounds {
+            level: 1,
+            length: 1000,
+            ..
+        })
+    ));
+
+    let mut overflowing = KTX2.to_vec();
+    overflowing[level_1_length..level_1_length + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert!(matches!(
+        CompressedImage::from_ktx2(&overflowing),
+        Err(ContainerError::LevelOutOfBounds { level: 1, .. })
+    ));
+}
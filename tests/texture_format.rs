@@ -0,0 +1,165 @@
+use image::{ColorType, DynamicImage};
+use learn_opengl::texture::{ColorSpace, TextureFormat};
+
+#[test]
+fn keeps_channel_count_of_8_bit_images() {
+    let cases = [
+        (ColorType::L8, TextureFormat::R8),
+        (ColorType::La8, TextureFormat::Rg8),
+        (ColorType::Rgb8, TextureFormat::Rgb8),
+        (ColorType::Rgba8, TextureFormat::Rgba8),
+    ];
+
+    for (color, format) in cases {
+        assert_eq!(
+            TextureFormat::for_color_type(color, ColorSpace::Linear),
+            format
+        );
+    }
+}
+
+#[test]
+fn srgb_picks_srgb_formats() {
+    assert_eq!(
+        TextureFormat::for_color_type(ColorType::Rgba8, ColorSpace::Srgb),
+        TextureFormat::Srgb8Alpha8
+    );
+    assert_eq!(
+        TextureFormat::for_color_type(ColorType::Rgb8, ColorSpace::Srgb),
+        TextureFormat::Srgb8
+    );
+    // No single channel sRGB format exists
+    assert_eq!(
+        TextureFormat::for_color_type(ColorType::L8, ColorSpace::Srgb),
+        TextureFormat::Srgb8
+    );
+}
+
+#[test]
+fn sixteen_bit_images_use_sixteen_bit_formats() {
+    let cases = [
+        (ColorType::L16, TextureFormat::R16),
+        (ColorType::La16, TextureFormat::Rg16),
+        (ColorType::Rgb16, TextureFormat::Rgb16),
+        (ColorType::Rgba16, TextureFormat::Rgba16),
+    ];
+
+    for (color, format) in cases {
+        assert_eq!(
+            TextureFormat::for_color_type(color, ColorSpace::Linear),
+            format
+        );
+        assert!(format.is_16_bit() && !format.is_float());
+        assert_eq!(format.pixel_type(), glow::UNSIGNED_SHORT);
+    }
+
+    // No 16-bit sRGB format exists, these are decoded to linear half floats
+    assert_eq!(
+        TextureFormat::for_color_type(ColorType::Rgba16, ColorSpace::Srgb),
+        TextureFormat::Rgba16F
+    );
+}
+
+#[test]
+fn float_images_use_float_formats() {
+    let cases = [
+        (ColorType::Rgb32F, TextureFormat::Rgb32F),
+        (ColorType::Rgba32F, TextureFormat::Rgba32F),
+    ];
+
+    for (color, format) in cases {
+        assert_eq!(
+            TextureFormat::for_color_type(color, ColorSpace::Linear),
+            format
+        );
+        assert!(format.is_float());
+        assert_eq!(format.pixel_type(), glow::FLOAT);
+    }
+}
+
+#[test]
+fn gl_enums_match_format() {
+    assert_eq!(TextureFormat::R8.internal_format(), glow::R8);
+    assert_eq!(TextureFormat::R8.pixel_format(), glow::RED);
+    assert_eq!(
+        TextureFormat::Srgb8Alpha8.internal_format(),
+        glow::SRGB8_ALPHA8
+    );
+    assert_eq!(TextureFormat::Srgb8Alpha8.pixel_format(), glow::RGBA);
+    assert_eq!(TextureFormat::Rgba16F.internal_format(), glow::RGBA16F);
+    assert_eq!(TextureFormat::Rg16.internal_format(), glow::RG16);
+    assert_eq!(TextureFormat::Rg8.pixel_type(), glow::UNSIGNED_BYTE);
+}
+
+#[test]
+fn single_channel_data_is_not_expanded() {
+    let image = DynamicImage::ImageLuma8(image::GrayImage::from_raw(3, 1, vec![1, 2, 3]).unwrap());
+    let format = TextureFormat::for_color_type(image.color(), ColorSpace::Linear);
+
+    let data = format.pixel_data(&image, ColorSpace::Linear);
+    assert_eq!(data, vec![1, 2, 3]);
+    assert_eq!(data.len(), 3 * format.pixel_size());
+}
+
+#[test]
+fn sixteen_bit_data_is_uploaded_unchanged() {
+    // Half floats can't tell 65534 from 65535, their spacing just below 1.0 is 1/2048
+    let source = vec![0u16, 1, 65534, 65535];
+    let image =
+        DynamicImage::ImageLuma16(image::ImageBuffer::from_raw(4, 1, source.clone()).unwrap());
+    let format = TextureFormat::for_color_type(image.color(), ColorSpace::Linear);
+
+    let data = format.pixel_data(&image, ColorSpace::Linear);
+    let values: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|bytes| u16::from_ne_bytes([bytes[0], bytes[1]]))
+        .collect();
+
+    assert_eq!(data.len(), 4 * format.pixel_size());
+    assert_eq!(values, source);
+}
+
+#[test]
+fn float_data_from_srgb_source_is_linear() {
+    let image = DynamicImage::ImageRgba16(
+        image::ImageBuffer::from_raw(1, 1, vec![32768u16, 32768, 32768, 65535]).unwrap(),
+    );
+
+    let data = TextureFormat::Rgba16F.pixel_data(&image, ColorSpace::Srgb);
+    let red = f32::from_ne_bytes([data[0], data[1], data[2], data[3]]);
+    let alpha = f32::from_ne_bytes([data[12], data[13], data[14], data[15]]);
+
+    // sRGB 0.5 is roughly 0.214 linear, alpha is never encoded
+    assert!((red - 0.214).abs() < 0.001);
+    assert_eq!(alpha, 1.0);
+}
+
+#[test]
+fn grayscale_formats_swizzle_to_gray() {
+    let gray = [glow::RED, glow::RED, glow::RED, glow::ONE];
+    let gray_alpha = [glow::RED, glow::RED, glow::RED, glow::GREEN];
+    let identity = [glow::RED, glow::GREEN, glow::BLUE, glow::ALPHA];
+
+    let cases = [
+        (TextureFormat::R8, gray),
+        (TextureFormat::R16, gray),
+        (TextureFormat::R16F, gray),
+        (TextureFormat::Rg8, gray_alpha),
+        (TextureFormat::Rg16, gray_alpha),
+        (TextureFormat::Rg16F, gray_alpha),
+        (TextureFormat::Rgb8, identity),
+        (TextureFormat::Srgb8, identity),
+        (TextureFormat::Rgba8, identity),
+        (TextureFormat::Srgb8Alpha8, identity),
+        (TextureFormat::Rgb16, identity),
+        (TextureFormat::Rgba16, identity),
+        (TextureFormat::Rgb16F, identity),
+        (TextureFormat::Rgba16F, identity),
+        (TextureFormat::Rgb32F, identity),
+        (TextureFormat::Rgba32F, identity),
+    ];
+
+    for (format, swizzle) in cases {
+        assert_eq!(format.swizzle(), swizzle, "{format:?}");
+    }
+}
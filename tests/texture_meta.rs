@@ -0,0 +1,126 @@
+use std::{
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+use learn_opengl::texture::{
+    ColorSpace, CreationError, Filter, MipFilter, Mipmaps, TextureMeta, TextureOptions, WrapMode,
+};
+
+/// An image path with a sidecar next to it, in a directory removed again on drop.
+struct Sidecar {
+    directory: PathBuf,
+    image_path: PathBuf,
+}
+
+impl Deref for Sidecar {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.image_path
+    }
+}
+
+impl Drop for Sidecar {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.directory);
+    }
+}
+
+/// Writes `source` as the sidecar of a fresh image path in a directory of its own.
+fn with_sidecar(name: &str, source: &str) -> Sidecar {
+    let directory =
+        std::env::temp_dir().join(format!("texture-meta-{}-{name}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+
+    let image_path = directory.join(name);
+    std::fs::write(TextureMeta::path_for(&image_path), source).unwrap();
+    Sidecar {
+        directory,
+        image_path,
+    }
+}
+
+#[test]
+fn sidecar_is_named_after_the_image() {
+    assert_eq!(
+        TextureMeta::path_for(Path::new("res/bricks.png")),
+        Path::new("res/bricks.png.meta")
+    );
+}
+
+#[test]
+fn missing_sidecar_is_not_an_error() {
+    let path = std::env::temp_dir().join("no-such-texture.png");
+    assert_eq!(TextureMeta::load(&path).unwrap(), None);
+}
+
+#[test]
+fn reads_every_setting() {
+    let path = with_sidecar(
+        "all.png",
+        r#"
+            color_space = "srgb"
+            wrap = "clamp_to_edge"
+            filter = "nearest"
+            mipmaps = { cpu = "kaiser" }
+            flip_vertically = false
+            compressed = "all.ktx2"
+        "#,
+    );
+
+    let meta = TextureMeta::load(&path).unwrap().unwrap();
+    assert_eq!(meta.color_space, Some(ColorSpace::Srgb));
+    assert_eq!(meta.wrap, Some(WrapMode::ClampToEdge));
+    assert_eq!(meta.filter, Some(Filter::Nearest));
+    assert_eq!(meta.mipmaps, Some(Mipmaps::Cpu(MipFilter::Kaiser)));
+    assert_eq!(meta.flip_vertically, Some(false));
+    // Relative to the sidecar, not the working directory
+    assert_eq!(meta.compressed, Some(path.with_file_name("all.ktx2")));
+}
+
+#[test]
+fn settings_override_options() {
+    let path = with_sidecar(
+        "override.png",
+        r#"
+            wrap = "mirrored_repeat"
+            filter = "nearest"
+            mipmaps = "none"
+        "#,
+    );
+    let meta = TextureMeta::load(&path).unwrap().unwrap();
+
+    let defaults = TextureOptions {
+        color_space: ColorSpace::Srgb,
+        ..Default::default()
+    };
+    let options = meta.apply(&defaults);
+
+    assert_eq!(options.sampler.wrap_s, WrapMode::MirroredRepeat);
+    assert_eq!(options.sampler.wrap_t, WrapMode::MirroredRepeat);
+    assert_eq!(options.sampler.min_filter, Filter::Nearest);
+    assert_eq!(options.sampler.mag_filter, Filter::Nearest);
+    assert_eq!(options.sampler.mipmap_filter, Some(Filter::Nearest));
+    assert_eq!(options.mipmaps, Mipmaps::None);
+
+    // Settings left out keep what the code asked for
+    assert_eq!(options.color_space, ColorSpace::Srgb);
+    assert!(options.flip_vertically);
+    assert_eq!(TextureMeta::default().apply(&defaults), defaults);
+}
+
+#[test]
+fn rejects_unknown_settings() {
+    let path = with_sidecar("typo.png", "colour_space = \"srgb\"\n");
+    assert!(matches!(
+        TextureMeta::load(&path),
+        Err(CreationError::MetaParsingFailed { .. })
+    ));
+
+    let path = with_sidecar("bad-value.png", "wrap = \"sideways\"\n");
+    assert!(matches!(
+        TextureMeta::load(&path),
+        Err(CreationError::MetaParsingFailed { .. })
+    ));
+}
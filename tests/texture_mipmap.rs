@@ -0,0 +1,103 @@
+use image::{ColorType, DynamicImage, GenericImageView, GrayImage, RgbImage, RgbaImage};
+use learn_opengl::texture::{generate_mipmaps, ColorSpace, MipFilter};
+
+const FILTERS: [MipFilter; 2] = [MipFilter::Lanczos3, MipFilter::Kaiser];
+
+#[test]
+fn halves_down_to_one_pixel() {
+    let cases = [
+        ((8, 4), vec![(4, 2), (2, 1), (1, 1)]),
+        ((5, 3), vec![(2, 1), (1, 1)]),
+        ((1, 1), vec![]),
+    ];
+
+    for ((width, height), expected) in cases {
+        let image = DynamicImage::ImageRgba8(RgbaImage::new(width, height));
+        let levels = generate_mipmaps(&image, MipFilter::Lanczos3, ColorSpace::Linear);
+        let sizes: Vec<_> = levels.iter().map(|level| level.dimensions()).collect();
+        assert_eq!(sizes, expected);
+    }
+}
+
+#[test]
+fn keeps_color_type() {
+    let images = [
+        DynamicImage::ImageLuma8(GrayImage::new(4, 4)),
+        DynamicImage::ImageRgb8(RgbImage::new(4, 4)),
+        DynamicImage::ImageRgba8(RgbaImage::new(4, 4)),
+        DynamicImage::new_rgb32f(4, 4),
+    ];
+
+    for image in images {
+        for level in generate_mipmaps(&image, MipFilter::Kaiser, ColorSpace::Linear) {
+            assert_eq!(level.color(), image.color());
+        }
+    }
+    assert_eq!(
+        generate_mipmaps(
+            &DynamicImage::ImageRgb8(RgbImage::new(2, 2)),
+            MipFilter::Kaiser,
+            ColorSpace::Srgb
+        )[0]
+        .color(),
+        ColorType::Rgb8
+    );
+}
+
+#[test]
+fn constant_image_stays_constant() {
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(
+        16,
+        16,
+        image::Rgba([200, 100, 50, 255]),
+    ));
+
+    for filter in FILTERS {
+        for color_space in [ColorSpace::Linear, ColorSpace::Srgb] {
+            for level in generate_mipmaps(&image, filter, color_space) {
+                for pixel in level.to_rgba8().pixels() {
+                    for (channel, expected) in pixel.0.iter().zip([200u8, 100, 50, 255]) {
+                        assert!(channel.abs_diff(expected) <= 1, "{pixel:?} with {filter:?}");
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn srgb_averages_in_linear_space() {
+    let image = DynamicImage::ImageLuma8(GrayImage::from_fn(2, 1, |x, _| {
+        image::Luma([x as u8 * 255])
+    }));
+
+    for filter in FILTERS {
+        let srgb = generate_mipmaps(&image, filter, ColorSpace::Srgb)[0].to_luma8()[(0, 0)].0[0];
+        let linear =
+            generate_mipmaps(&image, filter, ColorSpace::Linear)[0].to_luma8()[(0, 0)].0[0];
+
+        // Half of the light is 0.5 linear, 188 encoded, not the 128 a plain average gives
+        assert!((187..=189).contains(&srgb), "{srgb} with {filter:?}");
+        assert!((127..=128).contains(&linear), "{linear} with {filter:?}");
+    }
+}
+
+#[test]
+fn checkerboard_becomes_gray() {
+    let image = DynamicImage::ImageLuma8(GrayImage::from_fn(16, 16, |x, y| {
+        image::Luma([if (x + y) & 1 == 0 { 255 } else { 0 }])
+    }));
+
+    for filter in FILTERS {
+        let level = generate_mipmaps(&image, filter, ColorSpace::Linear)[0].to_luma8();
+        assert_eq!(level.dimensions(), (8, 8));
+
+        // Edge pixels repeat outwards, which tips the balance near the border
+        for y in 2..6 {
+            for x in 2..6 {
+                let pixel = level[(x, y)];
+                assert!(pixel.0[0].abs_diff(128) <= 2, "{pixel:?} with {filter:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use glam::Vec3;
+use image::{DynamicImage, GrayImage, Luma};
+use learn_opengl::texture::{normal_map_from_height, NormalFilter};
+
+const FILTERS: [NormalFilter; 2] = [NormalFilter::Sobel, NormalFilter::Scharr];
+
+fn height_map(f: impl Fn(u32, u32) -> u8) -> DynamicImage {
+    DynamicImage::ImageLuma8(GrayImage::from_fn(16, 16, |x, y| Luma([f(x, y)])))
+}
+
+/// Decodes the normal at an interior pixel, away from the clamped edges.
+fn normal_at(image: &DynamicImage, x: u32, y: u32) -> Vec3 {
+    let pixel = image.to_rgb8()[(x, y)].0;
+    Vec3::from_array(pixel.map(|value| value as f32 / 255.0 * 2.0 - 1.0))
+}
+
+fn assert_near(actual: Vec3, expected: Vec3) {
+    assert!(
+        actual.abs_diff_eq(expected, 0.02),
+        "{actual} is not close to {expected}"
+    );
+}
+
+#[test]
+fn flat_height_points_straight_out() {
+    let image = height_map(|_, _| 100);
+
+    for filter in FILTERS {
+        let normals = normal_map_from_height(&image, filter, 4.0);
+        assert!(normals
+            .to_rgb8()
+            .pixels()
+            .all(|pixel| pixel.0 == [128, 128, 255]));
+    }
+}
+
+#[test]
+fn slope_to_the_right_tilts_left() {
+    // Rises 10 / 255 per pixel, a strength of 25.5 makes that a slope of one
+    let image = height_map(|x, _| x as u8 * 10);
+    let expected = Vec3::new(-1.0, 0.0, 1.0).normalize();
+
+    for filter in FILTERS {
+        let normals = normal_map_from_height(&image, filter, 25.5);
+        assert_near(normal_at(&normals, 8, 8), expected);
+    }
+}
+
+#[test]
+fn slope_down_the_image_tilts_up() {
+    let image = height_map(|_, y| y as u8 * 10);
+    let expected = Vec3::new(0.0, 1.0, 1.0).normalize();
+
+    for filter in FILTERS {
+        let normals = normal_map_from_height(&image, filter, 25.5);
+        assert_near(normal_at(&normals, 8, 8), expected);
+    }
+}
+
+#[test]
+fn strength_scales_the_slope() {
+    let image = height_map(|x, y| (x + y) as u8 * 5);
+
+    for filter in FILTERS {
+        let gentle = normal_at(&normal_map_from_height(&image, filter, 10.0), 8, 8);
+        let steep = normal_at(&normal_map_from_height(&image, filter, 40.0), 8, 8);
+        assert!(steep.z < gentle.z);
+
+        let flat = normal_at(&normal_map_from_height(&image, filter, 0.0), 8, 8);
+        assert_near(flat, Vec3::new(0.0, 0.0, 1.0));
+
+        // The diagonal slope of 5 / 255 per pixel in both directions
+        let slope = 40.0 * 5.0 / 255.0;
+        assert_near(steep, Vec3::new(-slope, slope, 1.0).normalize());
+    }
+}
@@ -0,0 +1,90 @@
+use image::{GenericImageView, Rgba};
+use learn_opengl::texture::{
+    checkerboard, gradient, missing_image, noise, solid, uv_grid, GradientDirection, Noise,
+};
+
+const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+#[test]
+fn solid_fills_every_pixel() {
+    let color = Rgba([10, 20, 30, 40]);
+    let image = solid(3, 2, color);
+    assert_eq!(image.dimensions(), (3, 2));
+    assert!(image.pixels().all(|(_, _, pixel)| pixel == color));
+}
+
+#[test]
+fn checkerboard_alternates_cells() {
+    let image = checkerboard(8, 8, 2, WHITE, BLACK);
+
+    assert_eq!(image.get_pixel(0, 0), WHITE);
+    assert_eq!(image.get_pixel(1, 1), WHITE);
+    assert_eq!(image.get_pixel(2, 0), BLACK);
+    assert_eq!(image.get_pixel(0, 2), BLACK);
+    assert_eq!(image.get_pixel(2, 2), WHITE);
+    assert_eq!(image.get_pixel(7, 0), BLACK);
+}
+
+#[test]
+fn gradient_reaches_both_ends() {
+    let horizontal = gradient(5, 2, BLACK, WHITE, GradientDirection::Horizontal);
+    assert_eq!(horizontal.get_pixel(0, 1), BLACK);
+    assert_eq!(horizontal.get_pixel(2, 1), Rgba([128, 128, 128, 255]));
+    assert_eq!(horizontal.get_pixel(4, 1), WHITE);
+
+    let vertical = gradient(2, 3, BLACK, WHITE, GradientDirection::Vertical);
+    assert_eq!(vertical.get_pixel(1, 0), BLACK);
+    assert_eq!(vertical.get_pixel(1, 2), WHITE);
+}
+
+#[test]
+fn noise_depends_only_on_seed() {
+    for kind in [Noise::Perlin, Noise::Simplex, Noise::Worley] {
+        let first = noise(32, 32, kind, 8.0, 7).to_luma8();
+        assert_eq!(first, noise(32, 32, kind, 8.0, 7).to_luma8(), "{kind:?}");
+        assert_ne!(first, noise(32, 32, kind, 8.0, 8).to_luma8(), "{kind:?}");
+
+        // Features a few pixels apart give a wide spread of values
+        let min = first.pixels().map(|pixel| pixel.0[0]).min().unwrap();
+        let max = first.pixels().map(|pixel| pixel.0[0]).max().unwrap();
+        assert!(max - min > 64, "{kind:?} only spans {min} to {max}");
+    }
+}
+
+#[test]
+fn gradient_noise_is_centered() {
+    for kind in [Noise::Perlin, Noise::Simplex] {
+        let image = noise(128, 128, kind, 8.0, 1).to_luma8();
+        let mean = image.pixels().map(|pixel| pixel.0[0] as f32).sum::<f32>() / (128.0 * 128.0);
+        assert!((mean - 127.5).abs() < 16.0, "{kind:?} averages {mean}");
+    }
+}
+
+#[test]
+fn uv_grid_follows_texture_coordinates() {
+    let image = uv_grid(64, 64, 4);
+
+    // Lines on the border and between cells
+    assert_eq!(image.get_pixel(0, 10), WHITE);
+    assert_eq!(image.get_pixel(63, 10), WHITE);
+    assert_eq!(image.get_pixel(16, 10), WHITE);
+
+    let near_origin = image.get_pixel(2, 2);
+    let far_corner = image.get_pixel(61, 61);
+    assert!(near_origin[0] < 16 && near_origin[1] < 16);
+    assert!(far_corner[0] > 240 && far_corner[1] > 240);
+
+    let right = image.get_pixel(61, 2);
+    assert!(right[0] > 240 && right[1] < 16);
+}
+
+#[test]
+fn missing_image_is_magenta_and_black() {
+    let image = missing_image(16);
+    assert_eq!(image.get_pixel(0, 0), Rgba([255, 0, 255, 255]));
+    assert_eq!(image.get_pixel(2, 0), BLACK);
+    assert!(image
+        .pixels()
+        .all(|(_, _, pixel)| pixel == BLACK || pixel == Rgba([255, 0, 255, 255])));
+}